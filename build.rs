@@ -0,0 +1,36 @@
+use std::process::Command;
+
+fn locked_version(lock: &str, name: &str) -> String {
+    let header = format!("name = \"{}\"", name);
+    let mut lines = lock.lines();
+
+    while let Some(line) = lines.next() {
+        if line == header
+            && let Some(version) = lines.next().and_then(|line| line.strip_prefix("version = "))
+        {
+            return version.trim_matches('"').to_string();
+        }
+    }
+
+    "unknown".to_string()
+}
+
+fn main() {
+    let git_hash = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|hash| hash.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let lock = std::fs::read_to_string("Cargo.lock").unwrap_or_default();
+
+    println!("cargo:rustc-env=GIT_HASH={}", git_hash);
+    println!("cargo:rustc-env=SOLANA_SDK_VERSION={}", locked_version(&lock, "solana-sdk"));
+    println!("cargo:rustc-env=SPL_TOKEN_VERSION={}", locked_version(&lock, "spl-token"));
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+}
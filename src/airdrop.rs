@@ -0,0 +1,420 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{
+    instruction::Instruction,
+    message::Message,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+};
+use spl_associated_token_account_client::{
+    address::get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::instruction::transfer_checked;
+use axum::{Json, extract::State, http::StatusCode};
+use crate::pubkey::parse_pubkey;
+use crate::amount::{check_decimals, parse_ui_amount};
+use crate::state::AppState;
+use base64::{Engine as _, engine::general_purpose};
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct AirdropPlanRequest {
+    mint: String,
+    owner: String,
+    #[serde(default)]
+    decimals: Value,
+    csv: String,
+    #[serde(default, alias = "createAta")]
+    create_ata: bool,
+}
+
+#[derive(Serialize)]
+pub struct AirdropPlanResponse {
+    success: bool,
+    data: AirdropPlanData,
+}
+
+#[derive(Serialize)]
+pub struct AirdropPlanData {
+    mint: String,
+    source: String,
+    decimals: u8,
+    total_amount: u64,
+    recipients: Vec<RecipientData>,
+    transactions: Vec<TransactionData>,
+    invalid_rows: Vec<InvalidRow>,
+}
+
+#[derive(Serialize)]
+pub struct RecipientData {
+    line: u64,
+    wallet: String,
+    token_account: String,
+    amount: u64,
+}
+
+#[derive(Serialize)]
+pub struct TransactionData {
+    recipients: usize,
+    size: usize,
+    instructions: Vec<InstructionData>,
+}
+
+#[derive(Serialize)]
+pub struct InstructionData {
+    program_id: String,
+    accounts: Vec<AccountData>,
+    instruction_data: String,
+}
+
+#[derive(Serialize)]
+pub struct AccountData {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+#[derive(Serialize)]
+pub struct InvalidRow {
+    line: u64,
+    row: String,
+    error: String,
+}
+
+struct Recipient {
+    line: u64,
+    wallet: Pubkey,
+    token_account: Pubkey,
+    amount: u64,
+}
+
+fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let signatures = message.header.num_required_signatures as usize;
+
+    1 + signatures * 64 + message.serialize().len()
+}
+
+fn to_instruction_data(instruction: &Instruction) -> InstructionData {
+    InstructionData {
+        program_id: instruction.program_id.to_string(),
+        accounts: instruction
+            .accounts
+            .iter()
+            .map(|acc| AccountData {
+                pubkey: acc.pubkey.to_string(),
+                is_signer: acc.is_signer,
+                is_writable: acc.is_writable,
+            })
+            .collect(),
+        instruction_data: general_purpose::STANDARD.encode(&instruction.data),
+    }
+}
+
+pub async fn plan_airdrop(
+    State(state): State<AppState>,
+    Json(payload): Json<AirdropPlanRequest>,
+) -> Result<Json<AirdropPlanResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.mint.is_empty() || payload.owner.is_empty() || payload.csv.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let decimals = match check_decimals(&payload.decimals) {
+        Ok(decimals) => decimals,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
+
+    let mint_pubkey = match parse_pubkey(&payload.mint) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_mint",
+                error: "Invalid mint address".to_string(),
+            })));
+        }
+    };
+
+    let owner_pubkey = match parse_pubkey(&payload.owner) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_owner",
+                error: "Invalid owner address".to_string(),
+            })));
+        }
+    };
+
+    let token_program = state.config.token_program;
+    let source_ata = get_associated_token_address_with_program_id(&owner_pubkey, &mint_pubkey, &token_program);
+
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(payload.csv.as_bytes());
+
+    let mut recipients: Vec<Recipient> = Vec::new();
+    let mut invalid_rows: Vec<InvalidRow> = Vec::new();
+    let mut seen: HashMap<Pubkey, u64> = HashMap::new();
+
+    for (index, result) in reader.records().enumerate() {
+        let record = match result {
+            Ok(record) => record,
+            Err(e) => {
+                invalid_rows.push(InvalidRow {
+                    line: e.position().map(|p| p.line()).unwrap_or(0),
+                    row: String::new(),
+                    error: "Malformed CSV row".to_string(),
+                });
+                continue;
+            }
+        };
+
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let row = record.iter().collect::<Vec<_>>().join(",");
+
+        if index == 0 && record.get(0).is_some_and(|field| field.eq_ignore_ascii_case("wallet")) {
+            continue;
+        }
+
+        if record.len() != 2 {
+            invalid_rows.push(InvalidRow {
+                line,
+                row,
+                error: "Expected 2 columns: wallet,amount".to_string(),
+            });
+            continue;
+        }
+
+        let wallet = match parse_pubkey(&record[0]) {
+            Ok(pub_key) => pub_key,
+            Err(_) => {
+                invalid_rows.push(InvalidRow {
+                    line,
+                    row,
+                    error: "Invalid wallet address".to_string(),
+                });
+                continue;
+            }
+        };
+
+        let amount = match parse_ui_amount(&record[1], decimals) {
+            Ok(amount) => amount,
+            Err(e) => {
+                invalid_rows.push(InvalidRow {
+                    line,
+                    row,
+                    error: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        if let Some(first_line) = seen.get(&wallet) {
+            invalid_rows.push(InvalidRow {
+                line,
+                row,
+                error: format!("Duplicate recipient, first listed on line {}", first_line),
+            });
+            continue;
+        }
+        seen.insert(wallet, line);
+
+        recipients.push(Recipient {
+            line,
+            wallet,
+            token_account: get_associated_token_address_with_program_id(&wallet, &mint_pubkey, &token_program),
+            amount,
+        });
+    }
+
+    let total_amount = match recipients.iter().try_fold(0u64, |total, r| total.checked_add(r.amount)) {
+        Some(total) => total,
+        None => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "amount_overflow",
+                error: "Total airdrop amount exceeds maximum token supply".to_string(),
+            })));
+        }
+    };
+
+    let mut transactions: Vec<TransactionData> = Vec::new();
+    let mut batch: Vec<Instruction> = Vec::new();
+    let mut batch_recipients = 0;
+
+    for recipient in &recipients {
+        let mut instructions = Vec::new();
+
+        if payload.create_ata {
+            instructions.push(create_associated_token_account_idempotent(
+                &owner_pubkey,
+                &recipient.wallet,
+                &mint_pubkey,
+                &token_program,
+            ));
+        }
+
+        let transfer = match transfer_checked(
+            &token_program,
+            &source_ata,
+            &mint_pubkey,
+            &recipient.token_account,
+            &owner_pubkey,
+            &[],
+            recipient.amount,
+            decimals,
+        ) {
+            Ok(ix) => ix,
+            Err(_) => {
+                return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                    success: false,
+                    code: "instruction_failed",
+                    error: "Failed to create token transfer instruction".to_string(),
+                })));
+            }
+        };
+        instructions.push(transfer);
+
+        let mut candidate = batch.clone();
+        candidate.extend(instructions.iter().cloned());
+
+        if !batch.is_empty() && transaction_size(&candidate, &owner_pubkey) > PACKET_DATA_SIZE {
+            transactions.push(TransactionData {
+                recipients: batch_recipients,
+                size: transaction_size(&batch, &owner_pubkey),
+                instructions: batch.iter().map(to_instruction_data).collect(),
+            });
+            batch = instructions;
+            batch_recipients = 1;
+        } else {
+            batch = candidate;
+            batch_recipients += 1;
+        }
+    }
+
+    if !batch.is_empty() {
+        transactions.push(TransactionData {
+            recipients: batch_recipients,
+            size: transaction_size(&batch, &owner_pubkey),
+            instructions: batch.iter().map(to_instruction_data).collect(),
+        });
+    }
+
+    let response = AirdropPlanResponse {
+        success: true,
+        data: AirdropPlanData {
+            mint: payload.mint,
+            source: source_ata.to_string(),
+            decimals,
+            total_amount,
+            recipients: recipients
+                .iter()
+                .map(|r| RecipientData {
+                    line: r.line,
+                    wallet: r.wallet.to_string(),
+                    token_account: r.token_account.to_string(),
+                    amount: r.amount,
+                })
+                .collect(),
+            transactions,
+            invalid_rows,
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+
+    async fn plan(csv: &str, create_ata: bool) -> AirdropPlanData {
+        let state = AppState::for_tests(Config::from_toml(""));
+        let payload = serde_json::from_value(json!({
+            "mint": Pubkey::new_unique().to_string(),
+            "owner": Pubkey::new_unique().to_string(),
+            "decimals": 6,
+            "csv": csv,
+            "createAta": create_ata,
+        }))
+        .unwrap();
+
+        let Json(response) = plan_airdrop(State(state), Json(payload)).await.ok().unwrap();
+        response.data
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_rows_with_their_lines() {
+        let wallet = Pubkey::new_unique();
+        let csv = format!(
+            "wallet,amount\n{wallet},1.5\n{wallet}\nnot-a-wallet,1\n{other},0.0000001\n{third},-1\n",
+            wallet = wallet,
+            other = Pubkey::new_unique(),
+            third = Pubkey::new_unique(),
+        );
+
+        let data = plan(&csv, false).await;
+        assert_eq!(data.recipients.len(), 1);
+        assert_eq!(data.recipients[0].line, 2);
+        assert_eq!(data.total_amount, 1_500_000);
+
+        let lines: Vec<_> = data.invalid_rows.iter().map(|row| row.line).collect();
+        assert_eq!(lines, [3, 4, 5, 6]);
+        assert_eq!(data.invalid_rows[0].error, "Expected 2 columns: wallet,amount");
+        assert_eq!(data.invalid_rows[1].error, "Invalid wallet address");
+    }
+
+    #[tokio::test]
+    async fn rejects_duplicate_recipients() {
+        let wallet = Pubkey::new_unique();
+        let csv = format!("{wallet},1\n{other},2\n{wallet},3\n", wallet = wallet, other = Pubkey::new_unique());
+
+        let data = plan(&csv, false).await;
+        assert_eq!(data.recipients.len(), 2);
+        assert_eq!(data.total_amount, 3_000_000);
+        assert_eq!(data.invalid_rows.len(), 1);
+        assert_eq!(data.invalid_rows[0].line, 3);
+        assert_eq!(data.invalid_rows[0].error, "Duplicate recipient, first listed on line 1");
+    }
+
+    #[tokio::test]
+    async fn keeps_every_batch_under_the_packet_size() {
+        let csv: String = (0..60).map(|_| format!("{},1\n", Pubkey::new_unique())).collect();
+
+        for create_ata in [false, true] {
+            let data = plan(&csv, create_ata).await;
+            assert!(data.invalid_rows.is_empty());
+            assert!(data.transactions.len() > 1);
+            assert_eq!(data.transactions.iter().map(|tx| tx.recipients).sum::<usize>(), 60);
+
+            let per_recipient = if create_ata { 2 } else { 1 };
+            for tx in &data.transactions {
+                assert!(tx.size <= PACKET_DATA_SIZE);
+                assert_eq!(tx.instructions.len(), tx.recipients * per_recipient);
+            }
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+
+pub const SOL_DECIMALS: u8 = 9;
+pub const MAX_DECIMALS: u8 = 9;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum AmountInput {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    Text(String),
+}
+
+#[derive(Debug)]
+pub enum AmountError {
+    Invalid,
+    NotPositive,
+    TooPrecise(u8),
+    Overflow,
+    InvalidDecimals,
+}
+
+impl fmt::Display for AmountError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AmountError::Invalid => write!(f, "Invalid amount"),
+            AmountError::NotPositive => write!(f, "Amount must be greater than zero"),
+            AmountError::TooPrecise(0) => write!(f, "Amount must be a whole number"),
+            AmountError::TooPrecise(decimals) => write!(f, "Amount has more than {} decimal places", decimals),
+            AmountError::Overflow => write!(f, "Amount is too large"),
+            AmountError::InvalidDecimals => write!(f, "Decimals must be between 0 and {}", MAX_DECIMALS),
+        }
+    }
+}
+
+impl AmountError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AmountError::Invalid => "invalid_amount",
+            AmountError::NotPositive => "amount_not_positive",
+            AmountError::TooPrecise(_) => "amount_too_precise",
+            AmountError::Overflow => "amount_overflow",
+            AmountError::InvalidDecimals => "invalid_decimals",
+        }
+    }
+}
+
+impl AmountInput {
+    pub fn to_base_units(&self) -> Result<u64, AmountError> {
+        match self {
+            AmountInput::Unsigned(0) => Err(AmountError::NotPositive),
+            AmountInput::Unsigned(value) => Ok(*value),
+            AmountInput::Signed(value) if *value <= 0 => Err(AmountError::NotPositive),
+            AmountInput::Signed(value) => Ok(*value as u64),
+            AmountInput::Float(value) if *value <= 0.0 => Err(AmountError::NotPositive),
+            AmountInput::Float(value) if *value >= u64::MAX as f64 => Err(AmountError::Overflow),
+            AmountInput::Float(value) if value.fract() != 0.0 => Err(AmountError::TooPrecise(0)),
+            AmountInput::Float(_) => Err(AmountError::Invalid),
+            AmountInput::Text(value) => parse_ui_amount(value, 0),
+        }
+    }
+
+    pub fn to_ui_base_units(&self, decimals: u8) -> Result<u64, AmountError> {
+        if decimals > MAX_DECIMALS {
+            return Err(AmountError::InvalidDecimals);
+        }
+
+        match self {
+            AmountInput::Text(value) => parse_ui_amount(value, decimals),
+            AmountInput::Float(value) if *value > 0.0 => Err(AmountError::Invalid),
+            _ => self
+                .to_base_units()?
+                .checked_mul(10u64.pow(decimals as u32))
+                .ok_or(AmountError::Overflow),
+        }
+    }
+}
+
+pub fn check_decimals(decimals: &Value) -> Result<u8, AmountError> {
+    match decimals.as_i64() {
+        Some(decimals) if (0..=MAX_DECIMALS as i64).contains(&decimals) => Ok(decimals as u8),
+        _ => Err(AmountError::InvalidDecimals),
+    }
+}
+
+pub fn parse_ui_amount(value: &str, decimals: u8) -> Result<u64, AmountError> {
+    let value = value.trim();
+    if let Some(magnitude) = value.strip_prefix('-') {
+        if magnitude.starts_with('-') {
+            return Err(AmountError::Invalid);
+        }
+
+        return match parse_ui_amount(magnitude, decimals) {
+            Ok(_) | Err(AmountError::NotPositive | AmountError::Overflow) => Err(AmountError::NotPositive),
+            Err(e) => Err(e),
+        };
+    }
+
+    let (whole, fraction) = match value.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (value, ""),
+    };
+
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(AmountError::Invalid);
+    }
+
+    if !whole.chars().all(|c| c.is_ascii_digit()) || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(AmountError::Invalid);
+    }
+
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.len() > decimals as usize {
+        return Err(AmountError::TooPrecise(decimals));
+    }
+
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    let digits = digits.trim_start_matches('0');
+    if digits.is_empty() {
+        return Err(AmountError::NotPositive);
+    }
+
+    digits.parse::<u64>().map_err(|_| AmountError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw(value: &str) -> Result<u64, AmountError> {
+        serde_json::from_str::<AmountInput>(value).unwrap().to_base_units()
+    }
+
+    fn ui(value: &str, decimals: u8) -> Result<u64, AmountError> {
+        serde_json::from_str::<AmountInput>(value).unwrap().to_ui_base_units(decimals)
+    }
+
+    fn code(result: Result<u64, AmountError>) -> &'static str {
+        result.unwrap_err().code()
+    }
+
+    #[test]
+    fn treats_strings_and_numbers_alike() {
+        assert_eq!(raw("1000").unwrap(), 1000);
+        assert_eq!(raw("\"1000\"").unwrap(), 1000);
+        assert_eq!(ui("2", 6).unwrap(), 2_000_000);
+        assert_eq!(ui("\"2\"", 6).unwrap(), 2_000_000);
+        assert_eq!(raw("\"9007199254740993\"").unwrap(), 9_007_199_254_740_993);
+    }
+
+    #[test]
+    fn scales_ui_amounts_exactly() {
+        assert_eq!(ui("\"1.5\"", 9).unwrap(), 1_500_000_000);
+        assert_eq!(ui("\"0.000000001\"", 9).unwrap(), 1);
+        assert_eq!(ui("\"1.500\"", 2).unwrap(), 150);
+        assert_eq!(ui("\"7\"", 0).unwrap(), 7);
+    }
+
+    #[test]
+    fn rejects_excess_precision() {
+        assert_eq!(code(ui("\"1.0000001\"", 6)), "amount_too_precise");
+        assert_eq!(ui("\"1.0000001\"", 6).unwrap_err().to_string(), "Amount has more than 6 decimal places");
+        assert_eq!(raw("\"1.5\"").unwrap_err().to_string(), "Amount must be a whole number");
+        assert_eq!(raw("1.5").unwrap_err().to_string(), "Amount must be a whole number");
+    }
+
+    #[test]
+    fn detects_overflow_at_u64_max() {
+        assert_eq!(raw("18446744073709551615").unwrap(), u64::MAX);
+        assert_eq!(raw("\"18446744073709551615\"").unwrap(), u64::MAX);
+        assert_eq!(code(raw("\"18446744073709551616\"")), "amount_overflow");
+        assert_eq!(code(ui("18446744073709551615", 1)), "amount_overflow");
+        assert_eq!(code(ui("\"1844674407370955161.6\"", 1)), "amount_overflow");
+        assert_eq!(ui("\"1844674407370955161.5\"", 1).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn rejects_negative_and_zero() {
+        for value in ["0", "-1", "\"0\"", "\"-1\"", "\"0.000\"", "-0.5", "\"-18446744073709551616\""] {
+            assert_eq!(code(raw(value)), "amount_not_positive", "{}", value);
+        }
+        assert_eq!(code(ui("\"-0.5\"", 6)), "amount_not_positive");
+        assert_eq!(code(ui("-0.5", 6)), "amount_not_positive");
+        assert_eq!(code(raw("\"--1\"")), "invalid_amount");
+    }
+
+    #[test]
+    fn handles_leading_and_trailing_dots() {
+        assert_eq!(ui("\".5\"", 2).unwrap(), 50);
+        assert_eq!(ui("\"5.\"", 2).unwrap(), 500);
+        assert_eq!(code(ui("\".\"", 2)), "invalid_amount");
+        assert_eq!(code(ui("\"..5\"", 2)), "invalid_amount");
+        assert_eq!(code(raw("\".5\"")), "amount_too_precise");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for value in ["\"\"", "\"1e3\"", "\"+1\"", "\"1,000\"", "\"0x10\"", "\"1 000\""] {
+            assert_eq!(code(raw(value)), "invalid_amount", "{}", value);
+        }
+        assert_eq!(code(ui("1.5", 6)), "invalid_amount");
+        assert_eq!(code(ui("1", 10)), "invalid_decimals");
+    }
+}
@@ -0,0 +1,158 @@
+use serde::Serialize;
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use crate::config::ApiKeyConfig;
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+pub struct ApiKey {
+    pub name: String,
+    scopes: Vec<String>,
+}
+
+pub struct ApiKeys {
+    keys: HashMap<[u8; 32], Arc<ApiKey>>,
+}
+
+impl ApiKeys {
+    pub fn new(configured: &[ApiKeyConfig]) -> ApiKeys {
+        let keys = configured
+            .iter()
+            .map(|key| {
+                (key.key_hash, Arc::new(ApiKey {
+                    name: key.name.clone(),
+                    scopes: key.scopes.clone(),
+                }))
+            })
+            .collect();
+
+        ApiKeys { keys }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    fn lookup(&self, key: &str) -> Option<Arc<ApiKey>> {
+        let hash: [u8; 32] = Sha256::digest(key.as_bytes()).into();
+        self.keys.get(&hash).cloned()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Access {
+    Public,
+    Scope(&'static str),
+}
+
+fn reject(status: StatusCode, code: &'static str, error: &str) -> Response {
+    (status, Json(ErrorResponse {
+        success: false,
+        code,
+        error: error.to_string(),
+    }))
+        .into_response()
+}
+
+pub async fn require_api_key(State(state): State<AppState>, mut request: Request, next: Next) -> Response {
+    let scope = match request.extensions().get::<Access>() {
+        Some(Access::Public) => return next.run(request).await,
+        Some(Access::Scope(scope)) => *scope,
+        None => return reject(StatusCode::FORBIDDEN, "undeclared_route", "Route has no declared scope"),
+    };
+
+    if !state.api_keys.is_enabled() {
+        return next.run(request).await;
+    }
+
+    let key = match request.headers().get(API_KEY_HEADER).and_then(|value| value.to_str().ok()) {
+        Some(key) if !key.is_empty() => key,
+        _ => return reject(StatusCode::UNAUTHORIZED, "missing_api_key", "Missing API key"),
+    };
+
+    let api_key = match state.api_keys.lookup(key) {
+        Some(api_key) => api_key,
+        None => return reject(StatusCode::UNAUTHORIZED, "invalid_api_key", "Invalid API key"),
+    };
+
+    if !api_key.scopes.iter().any(|granted| granted == scope) {
+        return reject(StatusCode::FORBIDDEN, "missing_scope", &format!("API key is missing the {} scope", scope));
+    }
+
+    tracing::debug!(api_key = %api_key.name, "request authenticated");
+    request.extensions_mut().insert(api_key);
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use axum::{Extension, Router, body::Body, middleware, routing::get};
+    use tower::ServiceExt;
+
+    const KEY: &str = "test-api-key";
+
+    fn app(scopes: &str) -> Router {
+        let config = Config::from_toml(&format!(
+            "[[api_keys]]\nname = \"client\"\nsha256 = \"{}\"\nscopes = [{}]",
+            hex::encode(Sha256::digest(KEY.as_bytes())),
+            scopes,
+        ));
+        let state = AppState::for_tests(config);
+
+        Router::new()
+            .route("/undeclared", get(|| async { "ok" }))
+            .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key))
+            .merge(
+                Router::new()
+                    .route("/build", get(|| async { "ok" }))
+                    .route_layer(middleware::from_fn_with_state(state.clone(), require_api_key))
+                    .route_layer(Extension(Access::Scope("build"))),
+            )
+            .with_state(state)
+    }
+
+    async fn status(app: Router, path: &str, key: Option<&str>) -> StatusCode {
+        let mut request = Request::get(path);
+        if let Some(key) = key {
+            request = request.header(API_KEY_HEADER, key);
+        }
+
+        app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn rejects_routes_without_declared_access() {
+        assert_eq!(status(app(r#""build""#), "/undeclared", Some(KEY)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(app(r#""build""#), "/undeclared", None).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn rejects_keys_without_the_required_scope() {
+        assert_eq!(status(app(r#""verify""#), "/build", Some(KEY)).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(app(r#""build""#), "/build", Some(KEY)).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_and_unknown_keys() {
+        assert_eq!(status(app(r#""build""#), "/build", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(app(r#""build""#), "/build", Some("other-key")).await, StatusCode::UNAUTHORIZED);
+    }
+}
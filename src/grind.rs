@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{signature::Keypair, signer::Signer};
+use axum::{
+    Json,
+    extract::{State, ws::{Message, WebSocket, WebSocketUpgrade}},
+    http::StatusCode,
+    response::Response,
+};
+use tokio::sync::OwnedSemaphorePermit;
+use crate::state::AppState;
+use crate::telemetry;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use std::time::{Duration, Instant};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const MAX_PATTERN_LEN: usize = 8;
+const DEFAULT_MAX_ATTEMPTS: u64 = 50_000_000;
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+const MAX_TIMEOUT_MS: u64 = 300_000;
+const PROGRESS_INTERVAL_MS: u64 = 500;
+const FIRST_MESSAGE_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct GrindRequest {
+    #[serde(default, alias = "startsWith")]
+    prefix: String,
+    #[serde(default, alias = "endsWith")]
+    suffix: String,
+    #[serde(default, alias = "ignoreCase")]
+    ignore_case: bool,
+    threads: Option<usize>,
+    #[serde(alias = "maxAttempts")]
+    max_attempts: Option<u64>,
+    #[serde(alias = "timeoutMs")]
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct GrindResponse {
+    success: bool,
+    data: GrindData,
+}
+
+#[derive(Serialize)]
+pub struct GrindData {
+    found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pubkey: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    secret: Option<String>,
+    attempts: u64,
+    elapsed_ms: u64,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum GrindEvent {
+    Progress { attempts: u64, elapsed_ms: u64, rate: u64 },
+    Result(GrindData),
+    Error { error: String },
+}
+
+struct GrindParams {
+    prefix: String,
+    suffix: String,
+    ignore_case: bool,
+    threads: usize,
+    max_attempts: u64,
+    timeout: Duration,
+}
+
+struct GrindState {
+    attempts: AtomicU64,
+    stop: AtomicBool,
+    started: Instant,
+}
+
+impl GrindState {
+    fn new() -> GrindState {
+        GrindState {
+            attempts: AtomicU64::new(0),
+            stop: AtomicBool::new(false),
+            started: Instant::now(),
+        }
+    }
+}
+
+struct StopOnDrop(Arc<GrindState>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn validate_pattern(pattern: &str, ignore_case: bool) -> bool {
+    pattern.chars().all(|c| {
+        BASE58_ALPHABET.contains(c)
+            || (ignore_case && (BASE58_ALPHABET.contains(c.to_ascii_lowercase()) || BASE58_ALPHABET.contains(c.to_ascii_uppercase())))
+    })
+}
+
+fn grind_params(payload: GrindRequest) -> Result<GrindParams, String> {
+    if payload.prefix.is_empty() && payload.suffix.is_empty() {
+        return Err("Provide a prefix, a suffix, or both".to_string());
+    }
+
+    if payload.prefix.len() + payload.suffix.len() > MAX_PATTERN_LEN {
+        return Err(format!("Prefix and suffix may not exceed {} characters combined", MAX_PATTERN_LEN));
+    }
+
+    if !validate_pattern(&payload.prefix, payload.ignore_case) || !validate_pattern(&payload.suffix, payload.ignore_case) {
+        return Err("Prefix and suffix must only contain base58 characters".to_string());
+    }
+
+    let available = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let threads = payload.threads.unwrap_or((available / 2).max(1));
+    if threads == 0 || threads > available {
+        return Err(format!("Threads must be between 1 and {}", available));
+    }
+
+    let timeout_ms = payload.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS);
+    if timeout_ms == 0 || timeout_ms > MAX_TIMEOUT_MS {
+        return Err(format!("Timeout must be between 1 and {} ms", MAX_TIMEOUT_MS));
+    }
+
+    let (prefix, suffix) = if payload.ignore_case {
+        (payload.prefix.to_ascii_lowercase(), payload.suffix.to_ascii_lowercase())
+    } else {
+        (payload.prefix, payload.suffix)
+    };
+
+    Ok(GrindParams {
+        prefix,
+        suffix,
+        ignore_case: payload.ignore_case,
+        threads,
+        max_attempts: payload.max_attempts.unwrap_or(DEFAULT_MAX_ATTEMPTS),
+        timeout: Duration::from_millis(timeout_ms),
+    })
+}
+
+fn is_match(address: &str, params: &GrindParams) -> bool {
+    if params.ignore_case {
+        let address = address.to_ascii_lowercase();
+        address.starts_with(&params.prefix) && address.ends_with(&params.suffix)
+    } else {
+        address.starts_with(&params.prefix) && address.ends_with(&params.suffix)
+    }
+}
+
+fn run_grind(params: &GrindParams, state: &GrindState) -> Option<Keypair> {
+    let found: Mutex<Option<Keypair>> = Mutex::new(None);
+    let deadline = state.started + params.timeout;
+
+    std::thread::scope(|scope| {
+        for _ in 0..params.threads {
+            scope.spawn(|| {
+                while !state.stop.load(Ordering::Relaxed) {
+                    let attempt = state.attempts.fetch_add(1, Ordering::Relaxed);
+                    if attempt >= params.max_attempts || (attempt.is_multiple_of(1024) && Instant::now() >= deadline) {
+                        state.stop.store(true, Ordering::Relaxed);
+                        break;
+                    }
+
+                    let keypair = Keypair::new();
+                    if is_match(&keypair.pubkey().to_string(), params) {
+                        state.stop.store(true, Ordering::Relaxed);
+                        found.lock().unwrap().get_or_insert(keypair);
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    found.into_inner().unwrap()
+}
+
+fn grind_data(keypair: Option<Keypair>, params: &GrindParams, state: &GrindState) -> GrindData {
+    if keypair.is_some() {
+        telemetry::keypair_generated("grind");
+    }
+
+    GrindData {
+        found: keypair.is_some(),
+        pubkey: keypair.as_ref().map(|kp| kp.pubkey().to_string()),
+        secret: keypair.as_ref().map(|kp| kp.to_base58_string()),
+        attempts: state.attempts.load(Ordering::Relaxed).min(params.max_attempts),
+        elapsed_ms: state.started.elapsed().as_millis() as u64,
+    }
+}
+
+fn grind_permit(state: &AppState) -> Result<OwnedSemaphorePermit, (StatusCode, Json<ErrorResponse>)> {
+    state.grinds.clone().try_acquire_owned().map_err(|_| {
+        (StatusCode::TOO_MANY_REQUESTS, Json(ErrorResponse {
+            success: false,
+            code: "grind_limit",
+            error: "Too many keypair searches in progress".to_string(),
+        }))
+    })
+}
+
+pub async fn grind_keypair(
+    State(state): State<AppState>,
+    Json(payload): Json<GrindRequest>,
+) -> Result<Json<GrindResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let params = match grind_params(payload) {
+        Ok(params) => params,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_grind_params",
+                error,
+            })));
+        }
+    };
+
+    let permit = grind_permit(&state)?;
+    let grind = Arc::new(GrindState::new());
+    let _stop = StopOnDrop(grind.clone());
+
+    let data = match tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        let keypair = run_grind(&params, &grind);
+        grind_data(keypair, &params, &grind)
+    })
+    .await
+    {
+        Ok(data) => data,
+        Err(_) => {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse {
+                success: false,
+                code: "grind_failed",
+                error: "Keypair search failed".to_string(),
+            })));
+        }
+    };
+
+    Ok(Json(GrindResponse {
+        success: true,
+        data,
+    }))
+}
+
+pub async fn grind_keypair_ws(
+    State(state): State<AppState>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, (StatusCode, Json<ErrorResponse>)> {
+    let in_flight = match state.rate_limiter.try_acquire_in_flight() {
+        Some(permit) => permit,
+        None => {
+            return Err((StatusCode::TOO_MANY_REQUESTS, Json(ErrorResponse {
+                success: false,
+                code: "concurrency_limit",
+                error: "Server is at its concurrent request limit".to_string(),
+            })));
+        }
+    };
+    let permit = grind_permit(&state)?;
+    let timeout = state.config.timeout_for("keypair");
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        let _in_flight = in_flight;
+        if tokio::time::timeout(timeout, stream_grind(socket, permit)).await.is_err() {
+            tracing::debug!("Keypair search socket closed after {:?}", timeout);
+        }
+    }))
+}
+
+async fn send_event(socket: &mut WebSocket, event: &GrindEvent) -> bool {
+    let text = match serde_json::to_string(event) {
+        Ok(text) => text,
+        Err(_) => return false,
+    };
+
+    socket.send(Message::Text(text.into())).await.is_ok()
+}
+
+async fn stream_grind(mut socket: WebSocket, permit: OwnedSemaphorePermit) {
+    let first_message = tokio::time::timeout(Duration::from_secs(FIRST_MESSAGE_TIMEOUT_SECS), socket.recv()).await;
+    let payload = match first_message {
+        Ok(Some(Ok(Message::Text(text)))) => serde_json::from_str::<GrindRequest>(&text).map_err(|e| e.to_string()),
+        _ => return,
+    };
+
+    let params = match payload.and_then(grind_params) {
+        Ok(params) => Arc::new(params),
+        Err(error) => {
+            send_event(&mut socket, &GrindEvent::Error { error }).await;
+            return;
+        }
+    };
+
+    let state = Arc::new(GrindState::new());
+    let _stop = StopOnDrop(state.clone());
+
+    let mut task = {
+        let params = params.clone();
+        let state = state.clone();
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            run_grind(&params, &state)
+        })
+    };
+
+    let mut ticker = tokio::time::interval(Duration::from_millis(PROGRESS_INTERVAL_MS));
+    ticker.tick().await;
+
+    loop {
+        tokio::select! {
+            result = &mut task => {
+                let event = match result {
+                    Ok(keypair) => GrindEvent::Result(grind_data(keypair, &params, &state)),
+                    Err(_) => GrindEvent::Error { error: "Keypair search failed".to_string() },
+                };
+                send_event(&mut socket, &event).await;
+                break;
+            }
+            _ = ticker.tick() => {
+                let attempts = state.attempts.load(Ordering::Relaxed).min(params.max_attempts);
+                let elapsed_ms = state.started.elapsed().as_millis() as u64;
+                let rate = attempts * 1000 / elapsed_ms.max(1);
+
+                if !send_event(&mut socket, &GrindEvent::Progress { attempts, elapsed_ms, rate }).await {
+                    state.stop.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                if !matches!(message, Some(Ok(Message::Text(_) | Message::Binary(_) | Message::Ping(_) | Message::Pong(_)))) {
+                    state.stop.store(true, Ordering::Relaxed);
+                    break;
+                }
+            }
+        }
+    }
+
+    let _ = socket.send(Message::Close(None)).await;
+}
@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use axum::{Json, extract::State, http::StatusCode};
+use crate::state::AppState;
+use crate::telemetry;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+const RPC_PROBE_TIMEOUT_SECS: u64 = 2;
+const RPC_PROBE_INTERVAL_SECS: u64 = 10;
+const RPC_PROBE_MAX_AGE_SECS: u64 = 3 * RPC_PROBE_INTERVAL_SECS;
+
+pub struct RpcProbe {
+    url: String,
+    client: reqwest::Client,
+    last: Mutex<Option<(Instant, bool)>>,
+}
+
+impl RpcProbe {
+    pub fn new(url: &str) -> Option<Arc<RpcProbe>> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(RPC_PROBE_TIMEOUT_SECS))
+            .build()
+            .ok()?;
+
+        let probe = Arc::new(RpcProbe {
+            url: url.to_string(),
+            client,
+            last: Mutex::new(None),
+        });
+        tokio::spawn(probe_rpc(Arc::downgrade(&probe)));
+
+        Some(probe)
+    }
+
+    fn status(&self) -> Result<(), String> {
+        match *self.last.lock().unwrap() {
+            None => Err("not checked yet".to_string()),
+            Some((checked, _)) if checked.elapsed() > Duration::from_secs(RPC_PROBE_MAX_AGE_SECS) => {
+                Err("health check is stale".to_string())
+            }
+            Some((_, true)) => Ok(()),
+            Some((_, false)) => Err("rpc node is unhealthy".to_string()),
+        }
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "getHealth"}))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .json::<RpcHealthResponse>()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match (response.result.as_deref(), response.error) {
+            (Some("ok"), _) => Ok(()),
+            (_, Some(error)) => Err(error.message),
+            _ => Err("unexpected getHealth response".to_string()),
+        }
+    }
+}
+
+async fn probe_rpc(probe: Weak<RpcProbe>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(RPC_PROBE_INTERVAL_SECS));
+
+    loop {
+        interval.tick().await;
+        let probe = match probe.upgrade() {
+            Some(probe) => probe,
+            None => break,
+        };
+
+        let result = probe.check().await;
+        if let Err(e) = &result {
+            tracing::warn!("RPC health check failed: {}", e);
+        }
+        *probe.last.lock().unwrap() = Some((Instant::now(), result.is_ok()));
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcHealthResponse {
+    result: Option<String>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    message: String,
+}
+
+#[derive(Serialize)]
+pub struct HealthResponse {
+    success: bool,
+    data: HealthData,
+}
+
+#[derive(Serialize)]
+pub struct HealthData {
+    status: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct ReadyResponse {
+    success: bool,
+    data: ReadyData,
+}
+
+#[derive(Serialize)]
+pub struct ReadyData {
+    ready: bool,
+    checks: Vec<ReadyCheck>,
+}
+
+#[derive(Serialize)]
+pub struct ReadyCheck {
+    name: &'static str,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct VersionResponse {
+    success: bool,
+    data: VersionData,
+}
+
+#[derive(Serialize)]
+pub struct VersionData {
+    version: &'static str,
+    git_hash: &'static str,
+    solana_sdk: &'static str,
+    spl_token: &'static str,
+    routes: Vec<String>,
+    cluster: String,
+}
+
+fn ready_check(name: &'static str, result: Option<Result<(), String>>) -> ReadyCheck {
+    match result {
+        None => ReadyCheck { name, status: "skipped", error: None },
+        Some(Ok(())) => ReadyCheck { name, status: "ok", error: None },
+        Some(Err(error)) => ReadyCheck { name, status: "failed", error: Some(error) },
+    }
+}
+
+pub async fn healthz() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        success: true,
+        data: HealthData { status: "ok" },
+    })
+}
+
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    let accepting = if state.ready.load(Ordering::SeqCst) {
+        Ok(())
+    } else {
+        Err("shutting down".to_string())
+    };
+
+    let keystore = state.keystore.as_ref().map(|keystore| {
+        keystore.check().map_err(|e| {
+            tracing::warn!("Keystore readiness check failed: {}", e);
+            "keystore file is unavailable".to_string()
+        })
+    });
+
+    let rpc = state.rpc_probe.as_ref().map(|probe| probe.status());
+
+    let checks = vec![
+        ready_check("accepting", Some(accepting)),
+        ready_check("keystore", keystore),
+        ready_check("rpc", rpc),
+    ];
+    let ready = checks.iter().all(|check| check.status != "failed");
+
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (status, Json(ReadyResponse {
+        success: ready,
+        data: ReadyData { ready, checks },
+    }))
+}
+
+pub fn report_features(state: &AppState) {
+    for (feature, enabled) in [
+        ("keystore", state.keystore.is_some()),
+        ("remote_signer", state.remote_signer.is_some()),
+        ("tls", state.config.tls.is_some()),
+        ("api_keys", state.api_keys.is_enabled()),
+    ] {
+        telemetry::feature_enabled(feature, enabled);
+    }
+}
+
+pub async fn version(State(state): State<AppState>) -> Json<VersionResponse> {
+    Json(VersionResponse {
+        success: true,
+        data: VersionData {
+            version: env!("CARGO_PKG_VERSION"),
+            git_hash: env!("GIT_HASH"),
+            solana_sdk: env!("SOLANA_SDK_VERSION"),
+            spl_token: env!("SPL_TOKEN_VERSION"),
+            routes: state.config.routes.clone(),
+            cluster: state.config.cluster.clone(),
+        },
+    })
+}
@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signature};
+use solana_ed25519_program::{
+    DATA_START, PUBKEY_SERIALIZED_SIZE, SIGNATURE_SERIALIZED_SIZE, new_ed25519_instruction_with_signature,
+};
+use solana_secp256k1_program::{self as secp256k1_program, new_secp256k1_instruction_with_signature};
+use axum::{Json, extract::State, http::StatusCode};
+use base64::{Engine as _, engine::general_purpose};
+use serde_json::Value;
+use crate::message::{check_signature_encoding, decode_message, decode_signature, encoding_option, message_signer};
+use crate::pubkey::parse_pubkey;
+use crate::redact::Redacted;
+use crate::secp256k1::{
+    checksum_address, encode_signature, eth_address, message_hash, parse_eth_address, parse_secret_key,
+    parse_signature, signed_bytes,
+};
+use crate::state::AppState;
+use crate::telemetry;
+
+const MAX_ED25519_MESSAGE_LEN: usize = u16::MAX as usize - DATA_START - PUBKEY_SERIALIZED_SIZE - SIGNATURE_SERIALIZED_SIZE;
+const MAX_SECP256K1_MESSAGE_LEN: usize = u16::MAX as usize
+    - secp256k1_program::DATA_START
+    - secp256k1_program::HASHED_PUBKEY_SERIALIZED_SIZE
+    - secp256k1_program::SIGNATURE_SERIALIZED_SIZE
+    - 1;
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct Ed25519InstructionRequest {
+    message: String,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    signature: Option<String>,
+    #[serde(alias = "signatureEncoding")]
+    signature_encoding: Option<String>,
+    pubkey: Option<String>,
+    #[serde(default)]
+    secret: Redacted<Value>,
+    #[serde(alias = "keyId")]
+    key_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct InstructionResponse {
+    success: bool,
+    data: InstructionData,
+}
+
+#[derive(Serialize)]
+pub struct InstructionData {
+    program_id: String,
+    accounts: Vec<AccountData>,
+    instruction_data: String,
+    pubkey: String,
+    signature: String,
+}
+
+#[derive(Deserialize)]
+pub struct Secp256k1InstructionRequest {
+    message: String,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    #[serde(default = "default_personal_sign", alias = "personalSign")]
+    personal_sign: bool,
+    signature: Option<String>,
+    address: Option<String>,
+    #[serde(default)]
+    secret: Redacted<Option<String>>,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1InstructionResponse {
+    success: bool,
+    data: Secp256k1InstructionData,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1InstructionData {
+    program_id: String,
+    accounts: Vec<AccountData>,
+    instruction_data: String,
+    address: String,
+    signature: String,
+}
+
+#[derive(Serialize)]
+pub struct AccountData {
+    pubkey: String,
+    is_signer: bool,
+    is_writable: bool,
+}
+
+fn default_personal_sign() -> bool {
+    true
+}
+
+fn account_data(instruction: &Instruction) -> Vec<AccountData> {
+    instruction
+        .accounts
+        .iter()
+        .map(|account| AccountData {
+            pubkey: account.pubkey.to_string(),
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect()
+}
+
+fn instruction_data(instruction: Instruction, pubkey: &Pubkey, signature: &Signature) -> InstructionData {
+    InstructionData {
+        program_id: instruction.program_id.to_string(),
+        accounts: account_data(&instruction),
+        instruction_data: general_purpose::STANDARD.encode(&instruction.data),
+        pubkey: pubkey.to_string(),
+        signature: signature.to_string(),
+    }
+}
+
+pub async fn ed25519_instruction(
+    State(state): State<AppState>,
+    Json(payload): Json<Ed25519InstructionRequest>,
+) -> Result<Json<InstructionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.message.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let message_encoding = encoding_option(payload.message_encoding.as_deref(), "utf8");
+    let message = match decode_message(&payload.message, &message_encoding) {
+        Ok(message) => message,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_message",
+                error,
+            })));
+        }
+    };
+
+    if message.len() > MAX_ED25519_MESSAGE_LEN {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "message_too_long",
+            error: format!("Message may not exceed {} bytes", MAX_ED25519_MESSAGE_LEN),
+        })));
+    }
+
+    let (pubkey, signature) = match (&payload.signature, &payload.pubkey) {
+        (Some(signature), Some(pubkey)) => {
+            if !payload.secret.is_null() || payload.key_id.is_some() {
+                return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                    success: false,
+                    code: "conflicting_fields",
+                    error: "Provide either signature and pubkey or a signing key, not both".to_string(),
+                })));
+            }
+
+            let pubkey = match parse_pubkey(pubkey) {
+                Ok(pubkey) => pubkey,
+                Err(_) => {
+                    return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                        success: false,
+                        code: "invalid_pubkey",
+                        error: "Invalid public key format".to_string(),
+                    })));
+                }
+            };
+
+            let signature_encoding = encoding_option(payload.signature_encoding.as_deref(), "base64");
+            let signature = match check_signature_encoding(&signature_encoding)
+                .ok()
+                .and_then(|_| decode_signature(signature, &signature_encoding))
+            {
+                Some(signature) => signature,
+                None => {
+                    return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                        success: false,
+                        code: "invalid_signature",
+                        error: "Invalid signature format".to_string(),
+                    })));
+                }
+            };
+
+            let valid = signature.verify(pubkey.as_ref(), &message);
+            telemetry::signature_verified("ed25519", valid);
+            if !valid {
+                return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                    success: false,
+                    code: "signature_mismatch",
+                    error: "Signature does not match message and public key".to_string(),
+                })));
+            }
+
+            (pubkey, signature)
+        }
+        (None, None) => {
+            let signer = match message_signer(&state, &payload.secret, payload.key_id.as_deref()) {
+                Ok(signer) => signer,
+                Err((status, code, error)) => {
+                    return Err((status, Json(ErrorResponse {
+                        success: false,
+                        code,
+                        error,
+                    })));
+                }
+            };
+
+            let signed = match signer.sign(&message).await {
+                Ok(signature) => {
+                    telemetry::signature_produced("ed25519");
+                    signer.pubkey().await.map(|pubkey| (pubkey, signature))
+                }
+                Err(e) => Err(e),
+            };
+
+            match signed {
+                Ok(signed) => signed,
+                Err(e) => {
+                    return Err((e.status(), Json(ErrorResponse {
+                        success: false,
+                        code: e.code(),
+                        error: e.to_string(),
+                    })));
+                }
+            }
+        }
+        _ => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_fields",
+                error: "Signature and pubkey must be provided together".to_string(),
+            })));
+        }
+    };
+
+    let signature_bytes: [u8; SIGNATURE_SERIALIZED_SIZE] = signature.into();
+    let instruction = new_ed25519_instruction_with_signature(&message, &signature_bytes, &pubkey.to_bytes());
+
+    Ok(Json(InstructionResponse {
+        success: true,
+        data: instruction_data(instruction, &pubkey, &signature),
+    }))
+}
+
+pub async fn secp256k1_instruction(
+    Json(payload): Json<Secp256k1InstructionRequest>,
+) -> Result<Json<Secp256k1InstructionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.message.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let message = match signed_bytes(&payload.message, payload.message_encoding.as_deref(), payload.personal_sign) {
+        Ok(message) => message,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_message",
+                error,
+            })));
+        }
+    };
+
+    if message.len() > MAX_SECP256K1_MESSAGE_LEN {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "message_too_long",
+            error: format!("Message may not exceed {} bytes", MAX_SECP256K1_MESSAGE_LEN),
+        })));
+    }
+
+    let signed = match (&payload.signature, &payload.address, &*payload.secret) {
+        (Some(signature), Some(address), None) => parse_eth_address(address).and_then(|address| {
+            let (signature, recovery_id) = parse_signature(signature)?;
+            let recovered = libsecp256k1::recover(&message_hash(&message), &signature, &recovery_id);
+            let valid = recovered.is_ok_and(|public_key| eth_address(&public_key) == address);
+            telemetry::signature_verified("secp256k1", valid);
+            if !valid {
+                return Err("Signature does not match message and address".to_string());
+            }
+            Ok((address, signature, recovery_id))
+        }),
+        (None, None, Some(secret)) => parse_secret_key(secret).map(|secret_key| {
+            let (signature, recovery_id) = libsecp256k1::sign(&message_hash(&message), &secret_key);
+            telemetry::signature_produced("secp256k1");
+            let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+            (eth_address(&public_key), signature, recovery_id)
+        }),
+        _ => Err("Provide either signature and address or secret".to_string()),
+    };
+
+    let (address, signature, recovery_id) = match signed {
+        Ok(signed) => signed,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_secp256k1_params",
+                error,
+            })));
+        }
+    };
+
+    let instruction = new_secp256k1_instruction_with_signature(
+        &message,
+        &signature.serialize(),
+        recovery_id.serialize(),
+        &address,
+    );
+
+    Ok(Json(Secp256k1InstructionResponse {
+        success: true,
+        data: Secp256k1InstructionData {
+            program_id: instruction.program_id.to_string(),
+            accounts: account_data(&instruction),
+            instruction_data: general_purpose::STANDARD.encode(&instruction.data),
+            address: checksum_address(&address),
+            signature: encode_signature(&signature, &recovery_id),
+        },
+    }))
+}
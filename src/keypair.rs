@@ -1,10 +1,20 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use solana_sdk::{signature::Keypair, signer::Signer};
-use axum::Json;
+use solana_sdk::signer::keypair::{keypair_from_seed, keypair_from_seed_and_derivation_path};
+use solana_derivation_path::DerivationPath;
+use bip39::Mnemonic;
+use axum::{Json, http::StatusCode};
+use base64::{Engine as _, engine::general_purpose};
+use crate::redact::Redacted;
+use crate::telemetry;
+
+const DEFAULT_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     success: bool,
+    code: &'static str,
     error: String,
 }
 
@@ -20,8 +30,140 @@ pub struct KeypairData {
     secret: String,
 }
 
+#[derive(Deserialize)]
+pub struct GenerateMnemonicRequest {
+    #[serde(default = "default_word_count", alias = "wordCount")]
+    words: usize,
+    #[serde(default)]
+    passphrase: Redacted<String>,
+    path: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct DeriveKeypairRequest {
+    mnemonic: Redacted<String>,
+    #[serde(default)]
+    passphrase: Redacted<String>,
+    path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MnemonicResponse {
+    success: bool,
+    data: MnemonicData,
+}
+
+#[derive(Serialize)]
+pub struct MnemonicData {
+    mnemonic: String,
+    path: String,
+    pubkey: String,
+    secret: String,
+}
+
+#[derive(Serialize)]
+pub struct DerivedKeypairResponse {
+    success: bool,
+    data: DerivedKeypairData,
+}
+
+#[derive(Serialize)]
+pub struct DerivedKeypairData {
+    path: String,
+    pubkey: String,
+    secret: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConvertKeypairRequest {
+    secret: Redacted<Value>,
+    format: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ConvertKeypairResponse {
+    success: bool,
+    data: ConvertKeypairData,
+}
+
+#[derive(Serialize)]
+pub struct ConvertKeypairData {
+    pubkey: String,
+    format: String,
+    base58: String,
+    json: Vec<u8>,
+    hex: String,
+    base64: String,
+}
+
+fn default_word_count() -> usize {
+    12
+}
+
+fn derive_from_mnemonic(mnemonic: &Mnemonic, passphrase: &str, path: &str) -> Result<Keypair, String> {
+    let derivation_path = match DerivationPath::from_absolute_path_str(path) {
+        Ok(derivation_path) => derivation_path,
+        Err(_) => return Err("Invalid derivation path".to_string()),
+    };
+
+    let seed = mnemonic.to_seed(passphrase);
+
+    keypair_from_seed_and_derivation_path(&seed, Some(derivation_path))
+        .map_err(|_| "Failed to derive keypair".to_string())
+}
+
+fn detect_secret_format(secret: &Value) -> &'static str {
+    match secret {
+        Value::Array(_) => "json",
+        Value::String(text) if text.trim_start().starts_with('[') => "json",
+        Value::String(text) if text.len() == 128 && text.chars().all(|c| c.is_ascii_hexdigit()) => "hex",
+        Value::String(text) if bs58::decode(text).into_vec().is_ok() => "base58",
+        _ => "base64",
+    }
+}
+
+fn decode_secret_bytes(secret: &Value, format: &str) -> Result<Vec<u8>, String> {
+    match (format, secret) {
+        ("json", Value::Array(_)) => serde_json::from_value::<Vec<u8>>(secret.clone())
+            .map_err(|_| "Invalid JSON keypair, expected an array of bytes".to_string()),
+        ("json", Value::String(text)) => serde_json::from_str::<Vec<u8>>(text)
+            .map_err(|_| "Invalid JSON keypair, expected an array of bytes".to_string()),
+        ("base58", Value::String(text)) => bs58::decode(text)
+            .into_vec()
+            .map_err(|_| "Invalid base58 encoding in secret key".to_string()),
+        ("hex", Value::String(text)) => hex::decode(text.trim_start_matches("0x"))
+            .map_err(|_| "Invalid hex encoding in secret key".to_string()),
+        ("base64", Value::String(text)) => general_purpose::STANDARD
+            .decode(text)
+            .map_err(|_| "Invalid base64 encoding in secret key".to_string()),
+        ("json" | "base58" | "hex" | "base64", _) => Err(format!("Secret key in {} format must be a string", format)),
+        _ => Err(format!("Unsupported secret key format: {}", format)),
+    }
+}
+
+pub fn decode_secret(secret: &Value, format: Option<&str>) -> Result<Keypair, String> {
+    let format = format.unwrap_or_else(|| detect_secret_format(secret));
+    let bytes = decode_secret_bytes(secret, format)?;
+
+    if bytes.len() != 64 {
+        return Err("Invalid secret key length. Expected 64 bytes.".to_string());
+    }
+
+    let keypair = match keypair_from_seed(&bytes[..32]) {
+        Ok(keypair) => keypair,
+        Err(e) => return Err(format!("Invalid secret key format: {}", e)),
+    };
+
+    if keypair.pubkey().as_ref() != &bytes[32..] {
+        return Err("Public key does not match secret key".to_string());
+    }
+
+    Ok(keypair)
+}
+
 pub async fn get_keypair() -> Json<KeypairResponse> {
     let keypair = Keypair::new();
+    telemetry::keypair_generated("random");
     let address = keypair.pubkey();
     let secret = keypair.to_base58_string();
 
@@ -34,4 +176,228 @@ pub async fn get_keypair() -> Json<KeypairResponse> {
     };
 
     Json(response)
-}
\ No newline at end of file
+}
+
+pub async fn generate_mnemonic(
+    Json(payload): Json<GenerateMnemonicRequest>,
+) -> Result<Json<MnemonicResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.words != 12 && payload.words != 24 {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "invalid_word_count",
+            error: "Word count must be 12 or 24".to_string(),
+        })));
+    }
+
+    let mnemonic = match Mnemonic::generate(payload.words) {
+        Ok(mnemonic) => mnemonic,
+        Err(_) => {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse {
+                success: false,
+                code: "mnemonic_failed",
+                error: "Failed to generate mnemonic".to_string(),
+            })));
+        }
+    };
+
+    let path = payload.path.unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_string());
+    let keypair = match derive_from_mnemonic(&mnemonic, &payload.passphrase, &path) {
+        Ok(keypair) => keypair,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_derivation_path",
+                error,
+            })));
+        }
+    };
+    telemetry::keypair_generated("mnemonic");
+
+    let response = MnemonicResponse {
+        success: true,
+        data: MnemonicData {
+            mnemonic: mnemonic.to_string(),
+            path,
+            pubkey: keypair.pubkey().to_string(),
+            secret: keypair.to_base58_string(),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn derive_keypair(
+    Json(payload): Json<DeriveKeypairRequest>,
+) -> Result<Json<DerivedKeypairResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.mnemonic.trim().is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let phrase = payload.mnemonic.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mnemonic = match Mnemonic::parse(phrase.to_lowercase()) {
+        Ok(mnemonic) => mnemonic,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_mnemonic",
+                error: format!("Invalid mnemonic: {}", e),
+            })));
+        }
+    };
+
+    let path = payload.path.unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_string());
+    let keypair = match derive_from_mnemonic(&mnemonic, &payload.passphrase, &path) {
+        Ok(keypair) => keypair,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_derivation_path",
+                error,
+            })));
+        }
+    };
+    telemetry::keypair_generated("derived");
+
+    let response = DerivedKeypairResponse {
+        success: true,
+        data: DerivedKeypairData {
+            path,
+            pubkey: keypair.pubkey().to_string(),
+            secret: keypair.to_base58_string(),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn convert_keypair(
+    Json(payload): Json<ConvertKeypairRequest>,
+) -> Result<Json<ConvertKeypairResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.secret.is_null() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let format = payload
+        .format
+        .as_deref()
+        .map(|format| format.to_ascii_lowercase())
+        .unwrap_or_else(|| detect_secret_format(&payload.secret).to_string());
+
+    let keypair = match decode_secret(&payload.secret, Some(&format)) {
+        Ok(keypair) => keypair,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_secret_key",
+                error,
+            })));
+        }
+    };
+
+    let bytes = keypair.to_bytes();
+
+    let response = ConvertKeypairResponse {
+        success: true,
+        data: ConvertKeypairData {
+            pubkey: keypair.pubkey().to_string(),
+            format,
+            base58: keypair.to_base58_string(),
+            json: bytes.to_vec(),
+            hex: hex::encode(bytes),
+            base64: general_purpose::STANDARD.encode(bytes),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const ABANDON: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn request<T: serde::de::DeserializeOwned>(value: Value) -> Json<T> {
+        Json(serde_json::from_value(value).unwrap())
+    }
+
+    #[tokio::test]
+    async fn derives_the_standard_test_vector() {
+        let Json(response) = derive_keypair(request(json!({ "mnemonic": ABANDON }))).await.ok().unwrap();
+        assert_eq!(response.data.path, DEFAULT_DERIVATION_PATH);
+        assert_eq!(response.data.pubkey, "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+
+        let mixed_case = format!("  {}  ", ABANDON.to_uppercase().replace(' ', "\n "));
+        let Json(response) = derive_keypair(request(json!({ "mnemonic": mixed_case }))).await.ok().unwrap();
+        assert_eq!(response.data.pubkey, "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk");
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_mnemonics_and_paths() {
+        let (status, Json(body)) = derive_keypair(request(json!({ "mnemonic": "abandon abandon" }))).await.err().unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.code, "invalid_mnemonic");
+
+        let (_, Json(body)) = derive_keypair(request(json!({ "mnemonic": ABANDON, "path": "m/44'/x" }))).await.err().unwrap();
+        assert_eq!(body.code, "invalid_derivation_path");
+    }
+
+    #[tokio::test]
+    async fn generates_only_12_or_24_words() {
+        for words in [12, 24] {
+            let Json(response) = generate_mnemonic(request(json!({ "words": words }))).await.ok().unwrap();
+            assert_eq!(response.data.mnemonic.split(' ').count(), words);
+        }
+
+        for words in [0, 15, 25] {
+            let (status, Json(body)) = generate_mnemonic(request(json!({ "words": words }))).await.err().unwrap();
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body.code, "invalid_word_count");
+        }
+    }
+
+    #[tokio::test]
+    async fn converts_between_every_format() {
+        let keypair = Keypair::new();
+        let bytes = keypair.to_bytes();
+        let inputs = [
+            ("json", json!(bytes.to_vec())),
+            ("hex", json!(hex::encode(bytes))),
+            ("base64", json!(general_purpose::STANDARD.encode(bytes))),
+            ("base58", json!(keypair.to_base58_string())),
+        ];
+
+        for (format, secret) in inputs {
+            let Json(response) = convert_keypair(request(json!({ "secret": secret }))).await.ok().unwrap();
+            assert_eq!(response.data.format, format);
+            assert_eq!(response.data.pubkey, keypair.pubkey().to_string());
+            assert_eq!(response.data.json, bytes.to_vec());
+            assert_eq!(response.data.hex, hex::encode(bytes));
+            assert_eq!(response.data.base64, general_purpose::STANDARD.encode(bytes));
+            assert_eq!(response.data.base58, keypair.to_base58_string());
+
+            let Json(explicit) = convert_keypair(request(json!({ "secret": secret, "format": format }))).await.ok().unwrap();
+            assert_eq!(explicit.data.base58, keypair.to_base58_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_a_mismatched_public_key() {
+        let mut bytes = Keypair::new().to_bytes();
+        bytes[32..].copy_from_slice(Keypair::new().pubkey().as_ref());
+
+        let (status, Json(body)) = convert_keypair(request(json!({ "secret": bytes.to_vec() }))).await.err().unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.code, "invalid_secret_key");
+        assert_eq!(body.error, "Public key does not match secret key");
+    }
+}
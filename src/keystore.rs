@@ -0,0 +1,537 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose};
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload, rand_core::RngCore},
+};
+use crate::keypair::decode_secret;
+use crate::pubkey::parse_pubkey;
+use crate::redact::Redacted;
+use crate::state::AppState;
+use crate::telemetry;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const KEYSTORE_VERSION: u32 = 1;
+const ARGON2_MEMORY_KIB: u32 = 19 * 1024;
+const ARGON2_ITERATIONS: u32 = 2;
+const ARGON2_PARALLELISM: u32 = 1;
+const CHECK_PLAINTEXT: &[u8] = b"superdev-keystore";
+
+#[derive(Debug)]
+pub enum KeystoreError {
+    NotConfigured,
+    NotFound,
+    WrongPassphrase,
+    Corrupt(String),
+    Io(String),
+}
+
+impl fmt::Display for KeystoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeystoreError::NotConfigured => write!(f, "Keystore is not configured"),
+            KeystoreError::NotFound => write!(f, "Key not found"),
+            KeystoreError::WrongPassphrase => write!(f, "Keystore passphrase is incorrect"),
+            KeystoreError::Corrupt(reason) => write!(f, "Keystore file is corrupt: {}", reason),
+            KeystoreError::Io(reason) => write!(f, "Keystore file error: {}", reason),
+        }
+    }
+}
+
+impl KeystoreError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            KeystoreError::NotConfigured => StatusCode::SERVICE_UNAVAILABLE,
+            KeystoreError::NotFound => StatusCode::NOT_FOUND,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            KeystoreError::NotConfigured => "keystore_not_configured",
+            KeystoreError::NotFound => "key_not_found",
+            KeystoreError::WrongPassphrase => "keystore_passphrase",
+            KeystoreError::Corrupt(_) => "keystore_corrupt",
+            KeystoreError::Io(_) => "keystore_io",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct StoredKey {
+    id: String,
+    pubkey: String,
+    label: Option<String>,
+    created_at: u64,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    kdf: KdfParams,
+    cipher: String,
+    check_nonce: String,
+    check: String,
+    keys: Vec<StoredKey>,
+}
+
+pub struct Keystore {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305,
+    file: Mutex<KeystoreFile>,
+}
+
+fn derive_key(passphrase: &str, kdf: &KdfParams) -> Result<Key, KeystoreError> {
+    let salt = general_purpose::STANDARD
+        .decode(&kdf.salt)
+        .map_err(|_| KeystoreError::Corrupt("invalid salt".to_string()))?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(|e| KeystoreError::Corrupt(e.to_string()))?;
+
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| KeystoreError::Corrupt(e.to_string()))?;
+
+    Ok(key)
+}
+
+fn encrypt(cipher: &ChaCha20Poly1305, plaintext: &[u8], aad: &[u8]) -> (String, String) {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, Payload { msg: plaintext, aad })
+        .expect("encryption with a valid key cannot fail");
+
+    (general_purpose::STANDARD.encode(nonce), general_purpose::STANDARD.encode(ciphertext))
+}
+
+fn decrypt(cipher: &ChaCha20Poly1305, nonce: &str, ciphertext: &str, aad: &[u8]) -> Result<Vec<u8>, KeystoreError> {
+    let nonce = general_purpose::STANDARD
+        .decode(nonce)
+        .ok()
+        .filter(|nonce| nonce.len() == 12)
+        .ok_or_else(|| KeystoreError::Corrupt("invalid nonce".to_string()))?;
+    let ciphertext = general_purpose::STANDARD
+        .decode(ciphertext)
+        .map_err(|_| KeystoreError::Corrupt("invalid ciphertext".to_string()))?;
+
+    cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad })
+        .map_err(|_| KeystoreError::WrongPassphrase)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl Keystore {
+    pub fn open(path: PathBuf, passphrase: &str, create: bool) -> Result<Keystore, KeystoreError> {
+        if !path.exists() {
+            if create {
+                return Keystore::create(path, passphrase);
+            }
+            return Err(KeystoreError::Io(format!(
+                "{} does not exist, set keystore_create to create a new keystore",
+                path.display()
+            )));
+        }
+
+        let contents = fs::read_to_string(&path).map_err(|e| KeystoreError::Io(e.to_string()))?;
+        let file: KeystoreFile = serde_json::from_str(&contents).map_err(|e| KeystoreError::Corrupt(e.to_string()))?;
+
+        if file.version != KEYSTORE_VERSION {
+            return Err(KeystoreError::Corrupt(format!("unsupported version {}", file.version)));
+        }
+
+        if file.kdf.algorithm != "argon2id" || file.cipher != "chacha20poly1305" {
+            return Err(KeystoreError::Corrupt("unsupported kdf or cipher".to_string()));
+        }
+
+        let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &file.kdf)?);
+        decrypt(&cipher, &file.check_nonce, &file.check, b"check")?;
+
+        Ok(Keystore {
+            path,
+            cipher,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn create(path: PathBuf, passphrase: &str) -> Result<Keystore, KeystoreError> {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+
+        let kdf = KdfParams {
+            algorithm: "argon2id".to_string(),
+            salt: general_purpose::STANDARD.encode(salt),
+            memory_kib: ARGON2_MEMORY_KIB,
+            iterations: ARGON2_ITERATIONS,
+            parallelism: ARGON2_PARALLELISM,
+        };
+
+        let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &kdf)?);
+        let (check_nonce, check) = encrypt(&cipher, CHECK_PLAINTEXT, b"check");
+
+        let keystore = Keystore {
+            path,
+            cipher,
+            file: Mutex::new(KeystoreFile {
+                version: KEYSTORE_VERSION,
+                kdf,
+                cipher: "chacha20poly1305".to_string(),
+                check_nonce,
+                check,
+                keys: Vec::new(),
+            }),
+        };
+        keystore.save(&keystore.file.lock().unwrap())?;
+
+        Ok(keystore)
+    }
+
+    fn save(&self, file: &KeystoreFile) -> Result<(), KeystoreError> {
+        let contents = serde_json::to_vec_pretty(file).map_err(|e| KeystoreError::Io(e.to_string()))?;
+        let tmp_path = self.path.with_extension("tmp");
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let _ = fs::remove_file(&tmp_path);
+        let mut tmp = options.open(&tmp_path).map_err(|e| KeystoreError::Io(e.to_string()))?;
+        tmp.write_all(&contents)
+            .and_then(|_| tmp.sync_all())
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp_path);
+                KeystoreError::Io(e.to_string())
+            })
+    }
+
+    pub fn insert(&self, keypair: &Keypair, label: Option<String>) -> Result<KeyInfo, KeystoreError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let pubkey = keypair.pubkey().to_string();
+        let aad = format!("{}:{}", id, pubkey);
+        let (nonce, ciphertext) = encrypt(&self.cipher, &keypair.to_bytes(), aad.as_bytes());
+
+        let stored = StoredKey {
+            id,
+            pubkey,
+            label,
+            created_at: now(),
+            nonce,
+            ciphertext,
+        };
+
+        let mut file = self.file.lock().unwrap();
+        file.keys.push(stored.clone());
+        if let Err(e) = self.save(&file) {
+            file.keys.pop();
+            return Err(e);
+        }
+
+        Ok(KeyInfo::from(&stored))
+    }
+
+    pub fn keypair(&self, id: &str) -> Result<Keypair, KeystoreError> {
+        let stored = {
+            let file = self.file.lock().unwrap();
+            match file.keys.iter().find(|key| key.id == id) {
+                Some(stored) => stored.clone(),
+                None => return Err(KeystoreError::NotFound),
+            }
+        };
+
+        let aad = format!("{}:{}", stored.id, stored.pubkey);
+        let bytes = decrypt(&self.cipher, &stored.nonce, &stored.ciphertext, aad.as_bytes()).map_err(|e| match e {
+            KeystoreError::WrongPassphrase => KeystoreError::Corrupt(format!("key {} failed authentication", stored.id)),
+            e => e,
+        })?;
+
+        Keypair::try_from(bytes.as_slice()).map_err(|e| KeystoreError::Corrupt(e.to_string()))
+    }
+
+    pub fn pubkey(&self, id: &str) -> Result<Pubkey, KeystoreError> {
+        let file = self.file.lock().unwrap();
+        let stored = match file.keys.iter().find(|key| key.id == id) {
+            Some(stored) => stored,
+            None => return Err(KeystoreError::NotFound),
+        };
+
+        parse_pubkey(&stored.pubkey).map_err(|e| KeystoreError::Corrupt(e.to_string()))
+    }
+
+    pub fn check(&self) -> Result<(), KeystoreError> {
+        fs::File::open(&self.path)
+            .map(|_| ())
+            .map_err(|e| KeystoreError::Io(e.to_string()))
+    }
+
+    pub fn list(&self) -> Vec<KeyInfo> {
+        self.file.lock().unwrap().keys.iter().map(KeyInfo::from).collect()
+    }
+
+    pub fn remove(&self, id: &str) -> Result<(), KeystoreError> {
+        let mut file = self.file.lock().unwrap();
+        let index = match file.keys.iter().position(|key| key.id == id) {
+            Some(index) => index,
+            None => return Err(KeystoreError::NotFound),
+        };
+
+        let removed = file.keys.remove(index);
+        if let Err(e) = self.save(&file) {
+            file.keys.insert(index, removed);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Serialize)]
+pub struct KeyInfo {
+    id: String,
+    pubkey: String,
+    label: Option<String>,
+    created_at: u64,
+}
+
+impl From<&StoredKey> for KeyInfo {
+    fn from(stored: &StoredKey) -> Self {
+        KeyInfo {
+            id: stored.id.clone(),
+            pubkey: stored.pubkey.clone(),
+            label: stored.label.clone(),
+            created_at: stored.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateKeyRequest {
+    label: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportKeyRequest {
+    secret: Redacted<Value>,
+    format: Option<String>,
+    label: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct KeyResponse {
+    success: bool,
+    data: KeyInfo,
+}
+
+#[derive(Serialize)]
+pub struct KeyListResponse {
+    success: bool,
+    data: Vec<KeyInfo>,
+}
+
+#[derive(Serialize)]
+pub struct DeleteKeyResponse {
+    success: bool,
+    data: DeleteKeyData,
+}
+
+#[derive(Serialize)]
+pub struct DeleteKeyData {
+    id: String,
+    deleted: bool,
+}
+
+fn keystore_error(e: KeystoreError) -> (StatusCode, Json<ErrorResponse>) {
+    (e.status(), Json(ErrorResponse {
+        success: false,
+        code: e.code(),
+        error: e.to_string(),
+    }))
+}
+
+fn keystore(state: &AppState) -> Result<&Arc<Keystore>, (StatusCode, Json<ErrorResponse>)> {
+    state.keystore.as_ref().ok_or_else(|| keystore_error(KeystoreError::NotConfigured))
+}
+
+async fn blocking<T: Send + 'static>(
+    keystore: &Arc<Keystore>,
+    f: impl FnOnce(&Keystore) -> Result<T, KeystoreError> + Send + 'static,
+) -> Result<T, KeystoreError> {
+    let keystore = keystore.clone();
+    tokio::task::spawn_blocking(move || f(&keystore))
+        .await
+        .unwrap_or_else(|e| Err(KeystoreError::Io(e.to_string())))
+}
+
+pub async fn create_key(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateKeyRequest>,
+) -> Result<Json<KeyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let keystore = keystore(&state)?;
+
+    let info = blocking(keystore, move |keystore| keystore.insert(&Keypair::new(), payload.label))
+        .await
+        .map_err(keystore_error)?;
+    telemetry::keypair_generated("keystore");
+
+    Ok(Json(KeyResponse {
+        success: true,
+        data: info,
+    }))
+}
+
+pub async fn import_key(
+    State(state): State<AppState>,
+    Json(payload): Json<ImportKeyRequest>,
+) -> Result<Json<KeyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let keystore = keystore(&state)?;
+
+    if payload.secret.is_null() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let keypair = match decode_secret(&payload.secret, payload.format.as_deref()) {
+        Ok(keypair) => keypair,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_secret_key",
+                error,
+            })));
+        }
+    };
+
+    let info = blocking(keystore, move |keystore| keystore.insert(&keypair, payload.label))
+        .await
+        .map_err(keystore_error)?;
+
+    Ok(Json(KeyResponse {
+        success: true,
+        data: info,
+    }))
+}
+
+pub async fn list_keys(
+    State(state): State<AppState>,
+) -> Result<Json<KeyListResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let keystore = keystore(&state)?;
+
+    Ok(Json(KeyListResponse {
+        success: true,
+        data: keystore.list(),
+    }))
+}
+
+pub async fn delete_key(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<DeleteKeyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let keystore = keystore(&state)?;
+
+    let key_id = id.clone();
+    blocking(keystore, move |keystore| keystore.remove(&key_id))
+        .await
+        .map_err(keystore_error)?;
+
+    Ok(Json(DeleteKeyResponse {
+        success: true,
+        data: DeleteKeyData {
+            id,
+            deleted: true,
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("superdev-keystore-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn refuses_to_create_a_missing_keystore_implicitly() {
+        let path = temp_path("keystore.json");
+
+        let error = Keystore::open(path.clone(), PASSPHRASE, false).err().unwrap();
+        assert!(error.to_string().contains("does not exist"));
+        assert!(!path.exists());
+
+        Keystore::open(path.clone(), PASSPHRASE, true).unwrap();
+        assert!(path.exists());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn saves_keys_privately_and_reopens_them() {
+        let path = temp_path("keystore.json");
+        let keystore = Keystore::open(path.clone(), PASSPHRASE, true).unwrap();
+
+        let keypair = Keypair::new();
+        let info = keystore.insert(&keypair, Some("hot".to_string())).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+        assert!(!path.with_extension("tmp").exists());
+
+        let reopened = Keystore::open(path.clone(), PASSPHRASE, false).unwrap();
+        assert_eq!(reopened.keypair(&info.id).unwrap().pubkey(), keypair.pubkey());
+        assert!(matches!(Keystore::open(path.clone(), "wrong", false), Err(KeystoreError::WrongPassphrase)));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,36 +1,374 @@
-use axum::{routing::post, Router};
+use axum::{
+    Extension, Router,
+    extract::DefaultBodyLimit,
+    http::{HeaderName, StatusCode, header},
+    middleware,
+    routing::{delete, get, post},
+    serve::ListenerExt,
+};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::timeout::TimeoutLayer;
+use tower_http::trace::TraceLayer;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::{Semaphore, watch};
 
+mod airdrop;
+mod amount;
+mod auth;
+mod config;
+mod grind;
+mod health;
+mod instruction;
 mod keypair;
+mod keystore;
+mod logging;
 mod spl_token;
 mod mint_token;
 mod message;
+mod pda;
+mod pubkey;
+mod rate_limit;
+mod redact;
+mod rejection;
+mod secp256k1;
 mod send_sol;
 mod send_token_simple;
+mod signer;
+mod siws;
+mod state;
+mod telemetry;
+mod tls;
+mod transaction;
 
-use keypair::get_keypair;
+use airdrop::plan_airdrop;
+use auth::{Access, ApiKeys};
+use config::{Config, CorsConfig};
+use grind::{grind_keypair, grind_keypair_ws};
+use health::{RpcProbe, healthz, readyz, version};
+use instruction::{ed25519_instruction, secp256k1_instruction};
+use keypair::{convert_keypair, derive_keypair, generate_mnemonic, get_keypair};
+use keystore::{Keystore, create_key, delete_key, import_key, list_keys};
 use spl_token::spl_token_initialize_mint_instruction;
 use mint_token::mint_token;
-use message::{sign_message, verify_message};
+use message::{sign_message, verify_message, verify_message_batch};
+use pda::{create_program_address, find_program_address};
+use pubkey::inspect_pubkey;
+use rate_limit::RateLimiter;
+use secp256k1::{secp256k1_recover, secp256k1_sign, secp256k1_verify};
 use send_sol::send_sol;
 use send_token_simple::send_token;
+use signer::RemoteSigner;
+use siws::{SiwsStore, siws_challenge, siws_session, siws_verify};
+use state::AppState;
+use tls::TlsListener;
+use transaction::sign_transaction;
+
+fn routes(group: &str) -> Vec<(Access, Router<AppState>)> {
+    match group {
+        "keypair" => vec![(
+            Access::Scope("keys:generate"),
+            Router::new()
+                .route("/keypair", post(get_keypair))
+                .route("/keypair/mnemonic", post(generate_mnemonic))
+                .route("/keypair/derive", post(derive_keypair))
+                .route("/keypair/convert", post(convert_keypair))
+                .route("/keypair/grind", post(grind_keypair))
+                .route("/keypair/grind/ws", get(grind_keypair_ws)),
+        )],
+        "keystore" => vec![(
+            Access::Scope("keys:manage"),
+            Router::new()
+                .route("/keystore/keys", get(list_keys).post(create_key))
+                .route("/keystore/keys/import", post(import_key))
+                .route("/keystore/keys/{id}", delete(delete_key)),
+        )],
+        "token" => vec![(
+            Access::Scope("build"),
+            Router::new()
+                .route("/token/create", post(spl_token_initialize_mint_instruction))
+                .route("/token/mint", post(mint_token))
+                .route("/token/airdrop", post(plan_airdrop)),
+        )],
+        "message" => vec![
+            (Access::Scope("sign"), Router::new().route("/message/sign", post(sign_message))),
+            (
+                Access::Scope("verify"),
+                Router::new()
+                    .route("/message/verify", post(verify_message))
+                    .route("/message/verify/batch", post(verify_message_batch)),
+            ),
+        ],
+        "instruction" => vec![(
+            Access::Scope("sign"),
+            Router::new()
+                .route("/instruction/ed25519", post(ed25519_instruction))
+                .route("/instruction/secp256k1", post(secp256k1_instruction)),
+        )],
+        "secp256k1" => vec![
+            (Access::Scope("sign"), Router::new().route("/secp256k1/sign", post(secp256k1_sign))),
+            (
+                Access::Scope("verify"),
+                Router::new()
+                    .route("/secp256k1/verify", post(secp256k1_verify))
+                    .route("/secp256k1/recover", post(secp256k1_recover)),
+            ),
+        ],
+        "pda" => vec![(
+            Access::Scope("build"),
+            Router::new()
+                .route("/pda/find", post(find_program_address))
+                .route("/pda/create", post(create_program_address)),
+        )],
+        "pubkey" => vec![(Access::Scope("build"), Router::new().route("/pubkey/inspect", post(inspect_pubkey)))],
+        "send" => vec![(
+            Access::Scope("build"),
+            Router::new()
+                .route("/send/sol", post(send_sol))
+                .route("/send/token", post(send_token)),
+        )],
+        "transaction" => vec![(Access::Scope("sign"), Router::new().route("/transaction/sign", post(sign_transaction)))],
+        "auth" => vec![(
+            Access::Public,
+            Router::new()
+                .route("/auth/siws/challenge", post(siws_challenge))
+                .route("/auth/siws/verify", post(siws_verify))
+                .route("/auth/siws/session", get(siws_session)),
+        )],
+        _ => Vec::new(),
+    }
+}
+
+fn scoped(state: &AppState, access: Access, router: Router<AppState>) -> Router<AppState> {
+    router
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit::limit_by_api_key))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_api_key))
+        .route_layer(Extension(access))
+}
+
+fn cors_layer(cors: &CorsConfig) -> CorsLayer {
+    let allow_origin = if cors.allow_any_origin {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(cors.allowed_origins.clone())
+    };
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(cors.allowed_methods.clone())
+        .allow_headers(cors.allowed_headers.clone())
+        .expose_headers([HeaderName::from_static("x-request-id"), header::RETRY_AFTER])
+        .max_age(cors.max_age)
+}
+
+fn set_nodelay(stream: &tokio::net::TcpStream) {
+    if let Err(e) = stream.set_nodelay(true) {
+        tracing::debug!("Failed to set TCP_NODELAY: {}", e);
+    }
+}
+
+async fn shutdown_signal(ready: Arc<AtomicBool>, delay: Duration, draining: watch::Sender<bool>) {
+    let ctrl_c = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    ready.store(false, Ordering::SeqCst);
+    if !delay.is_zero() {
+        tracing::info!("Shutdown requested, reporting not ready for {:?} before draining", delay);
+        tokio::time::sleep(delay).await;
+    }
+
+    let _ = draining.send(true);
+}
 
 #[tokio::main]
 async fn main() {
-    let app = Router::new()
-        .route("/keypair", post(get_keypair))
-        .route("/token/create", post(spl_token_initialize_mint_instruction))
-        .route("/token/mint", post(mint_token))
-        .route("/message/sign", post(sign_message))
-        .route("/message/verify", post(verify_message))
-        .route("/send/sol", post(send_sol))
-        .route("/send/token", post(send_token));
-
-    let address = "0.0.0.0:3000";
-    let listener = tokio::net::TcpListener::bind(address)
-        .await
-        .unwrap();
-
-    println!("Server running on {}", address);
-    axum::serve(listener, app).await.unwrap();
-}
+    let config = match Config::load() {
+        Ok(config) => Arc::new(config),
+        Err(e) => {
+            eprintln!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
 
+    logging::init(config.log_level);
+
+    let metrics = match telemetry::install() {
+        Ok(metrics) => metrics,
+        Err(e) => {
+            tracing::error!("Failed to install metrics recorder: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let keystore = match &config.keystore {
+        Some(keystore) => match Keystore::open(keystore.path.clone(), &keystore.passphrase, keystore.create) {
+            Ok(keystore) => Some(Arc::new(keystore)),
+            Err(e) => {
+                tracing::error!("Failed to open keystore: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let remote_signer = match &config.remote_signer {
+        Some(remote) => match RemoteSigner::new(&remote.url, remote.token.clone()) {
+            Some(signer) => Some(Arc::new(signer)),
+            None => {
+                tracing::error!("Failed to create the remote signer client");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+
+    let state = AppState {
+        config: config.clone(),
+        api_keys: Arc::new(ApiKeys::new(&config.api_keys)),
+        rate_limiter: RateLimiter::new(&config),
+        keystore,
+        remote_signer,
+        rpc_probe: config.rpc_url.as_deref().and_then(RpcProbe::new),
+        siws: Arc::new(SiwsStore::default()),
+        grinds: Arc::new(Semaphore::new(config.max_concurrent_grinds)),
+        batches: Arc::new(Semaphore::new(config.max_concurrent_batches)),
+        ready: Arc::new(AtomicBool::new(true)),
+        metrics,
+    };
+    let ready = state.ready.clone();
+    health::report_features(&state);
+
+    if !state.api_keys.is_enabled() {
+        tracing::warn!("No api_keys configured, all routes are publicly accessible");
+    }
+
+    if config.routes.iter().any(|group| group == "auth") && config.siws_domains.is_empty() {
+        tracing::warn!("No siws_domains configured, Sign-In With Solana challenges will be refused");
+    }
+
+    let app = config
+        .routes
+        .iter()
+        .flat_map(|group| {
+            let timeout = TimeoutLayer::with_status_code(StatusCode::REQUEST_TIMEOUT, config.timeout_for(group));
+            routes(group).into_iter().map(move |(access, router)| (access, router.layer(timeout)))
+        })
+        .chain([
+            (
+                Access::Public,
+                Router::new()
+                    .route("/healthz", get(healthz))
+                    .route("/readyz", get(readyz))
+                    .route("/version", get(version)),
+            ),
+            (Access::Scope("metrics"), Router::new().route("/metrics", get(telemetry::metrics))),
+        ])
+        .fold(Router::new(), |app, (access, router)| app.merge(scoped(&state, access, router)))
+        .layer(DefaultBodyLimit::max(config.body_limit))
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit::limit_by_ip))
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit::limit_concurrency))
+        .layer(middleware::from_fn(rejection::json_rejections))
+        .layer(middleware::from_fn(telemetry::track_requests))
+        .layer(middleware::from_fn(logging::request_id_in_errors))
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(logging::request_span)
+                .on_response(logging::log_response),
+        );
+
+    let app = match &config.cors {
+        Some(cors) => app.layer(cors_layer(cors)),
+        None => app,
+    };
+
+    let app = app
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+        .with_state(state);
+
+    let listener = match tokio::net::TcpListener::bind(config.bind_address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to bind {}: {}", config.bind_address, e);
+            std::process::exit(1);
+        }
+    };
+
+    let (draining, mut drain_started) = watch::channel(false);
+    let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+    let shutdown = shutdown_signal(ready, config.shutdown_delay, draining);
+
+    let server: Pin<Box<dyn Future<Output = std::io::Result<()>> + Send>> = match &config.tls {
+        Some(tls) => {
+            let listener = match TlsListener::new(listener, tls) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    tracing::error!("Failed to configure TLS: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            tracing::info!("Server running on https://{} ({})", config.bind_address, config.cluster);
+            Box::pin(
+                axum::serve(listener.tap_io(|tls| set_nodelay(tls.get_ref().0)), make_service)
+                    .with_graceful_shutdown(shutdown)
+                    .into_future(),
+            )
+        }
+        None => {
+            tracing::info!("Server running on http://{} ({})", config.bind_address, config.cluster);
+            Box::pin(
+                axum::serve(listener.tap_io(|tcp| set_nodelay(tcp)), make_service)
+                    .with_graceful_shutdown(shutdown)
+                    .into_future(),
+            )
+        }
+    };
+
+    let drain_deadline = async {
+        if drain_started.wait_for(|draining| *draining).await.is_err() {
+            std::future::pending::<()>().await;
+        }
+        tracing::info!("Shutting down, draining connections for up to {:?}", config.drain_timeout);
+        tokio::time::sleep(config.drain_timeout).await;
+    };
+
+    tokio::select! {
+        result = server => {
+            if let Err(e) = result {
+                tracing::error!("Server error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        _ = drain_deadline => {
+            tracing::error!("Drain timeout elapsed with connections still open");
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,20 +1,40 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::{signature::Keypair, signer::Signer, signature::Signature};
-use axum::{Json, http::StatusCode};
-use std::str::FromStr;
+use solana_sdk::signature::Signature;
+use solana_offchain_message::{MessageFormat, OffchainMessage};
+use axum::{Json, extract::State, http::StatusCode};
+use crate::keypair::decode_secret;
+use crate::signer::{InlineSigner, SignerBackend, key_signer};
+use crate::pubkey::parse_pubkey;
+use crate::redact::Redacted;
+use crate::state::AppState;
+use crate::telemetry;
 use base64::{Engine as _, engine::general_purpose};
-use bs58;
+use serde_json::Value;
+use tokio::sync::OwnedSemaphorePermit;
+
+const MAX_BATCH_SIZE: usize = 5_000;
+const BATCH_CHUNK_MIN: usize = 64;
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     success: bool,
+    code: &'static str,
     error: String,
 }
 
 #[derive(Deserialize)]
 pub struct SignMessageRequest {
     message: String,
-    secret: String,
+    #[serde(default)]
+    secret: Redacted<Value>,
+    #[serde(alias = "keyId")]
+    key_id: Option<String>,
+    #[serde(default)]
+    offchain: bool,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    #[serde(alias = "signatureEncoding")]
+    signature_encoding: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -28,6 +48,10 @@ pub struct SignMessageData {
     signature: String,
     public_key: String,
     message: String,
+    message_encoding: String,
+    signature_encoding: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -35,6 +59,39 @@ pub struct VerifyMessageRequest {
     message: String,
     signature: String,
     pubkey: String,
+    #[serde(default)]
+    offchain: bool,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    #[serde(alias = "signatureEncoding")]
+    signature_encoding: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyBatchRequest {
+    items: Vec<VerifyMessageRequest>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyBatchResponse {
+    success: bool,
+    data: VerifyBatchData,
+}
+
+#[derive(Serialize)]
+pub struct VerifyBatchData {
+    all_valid: bool,
+    total: usize,
+    valid_count: usize,
+    results: Vec<VerifyBatchResult>,
+}
+
+#[derive(Serialize)]
+pub struct VerifyBatchResult {
+    index: usize,
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -48,125 +105,401 @@ pub struct VerifyMessageData {
     valid: bool,
     message: String,
     pubkey: String,
+    message_encoding: String,
+    signature_encoding: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
 }
 
+fn format_name(format: MessageFormat) -> &'static str {
+    match format {
+        MessageFormat::RestrictedAscii => "restricted_ascii",
+        MessageFormat::LimitedUtf8 => "limited_utf8",
+        MessageFormat::ExtendedUtf8 => "extended_utf8",
+    }
+}
+
+pub fn decode_message(message: &str, encoding: &str) -> Result<Vec<u8>, String> {
+    match encoding {
+        "utf8" => Ok(message.as_bytes().to_vec()),
+        "hex" => hex::decode(message.trim_start_matches("0x")).map_err(|_| "Invalid hex encoding in message".to_string()),
+        "base64" => general_purpose::STANDARD
+            .decode(message)
+            .map_err(|_| "Invalid base64 encoding in message".to_string()),
+        "base58" => bs58::decode(message)
+            .into_vec()
+            .map_err(|_| "Invalid base58 encoding in message".to_string()),
+        _ => Err(format!("Unsupported message encoding: {}", encoding)),
+    }
+}
+
+pub fn check_signature_encoding(encoding: &str) -> Result<(), String> {
+    match encoding {
+        "base64" | "base58" | "hex" => Ok(()),
+        _ => Err(format!("Unsupported signature encoding: {}", encoding)),
+    }
+}
+
+fn encode_signature(signature: &Signature, encoding: &str) -> String {
+    match encoding {
+        "base58" => signature.to_string(),
+        "hex" => hex::encode(signature),
+        _ => general_purpose::STANDARD.encode(signature),
+    }
+}
+
+pub fn decode_signature(signature: &str, encoding: &str) -> Option<Signature> {
+    let bytes = match encoding {
+        "base58" => bs58::decode(signature).into_vec().ok()?,
+        "hex" => hex::decode(signature.trim_start_matches("0x")).ok()?,
+        _ => general_purpose::STANDARD.decode(signature).ok()?,
+    };
+
+    Signature::try_from(bytes.as_slice()).ok()
+}
+
+pub fn encoding_option(encoding: Option<&str>, default: &str) -> String {
+    encoding.map(|encoding| encoding.to_ascii_lowercase()).unwrap_or_else(|| default.to_string())
+}
+
+fn signing_bytes(message: Vec<u8>, offchain: bool) -> Result<(Vec<u8>, Option<String>), String> {
+    if !offchain {
+        return Ok((message, None));
+    }
+
+    let offchain_message = match OffchainMessage::new(0, &message) {
+        Ok(offchain_message) => offchain_message,
+        Err(_) => return Err("Message must be UTF-8 text within the off-chain message size limit".to_string()),
+    };
+
+    match offchain_message.serialize() {
+        Ok(bytes) => Ok((bytes, Some(format_name(offchain_message.get_format()).to_string()))),
+        Err(_) => Err("Failed to serialize off-chain message".to_string()),
+    }
+}
+
+pub fn message_signer(
+    state: &AppState,
+    secret: &Value,
+    key_id: Option<&str>,
+) -> Result<Box<dyn SignerBackend>, (StatusCode, &'static str, String)> {
+    if secret.is_null() && key_id.is_none() {
+        return Err((StatusCode::BAD_REQUEST, "missing_fields", "Missing required fields".to_string()));
+    }
+
+    if !secret.is_null() && key_id.is_some() {
+        return Err((StatusCode::BAD_REQUEST, "conflicting_fields", "Provide either secret or keyId, not both".to_string()));
+    }
+
+    if let Some(key_id) = key_id {
+        return key_signer(state, key_id).map_err(|e| (e.status(), e.code(), e.to_string()));
+    }
+
+    match decode_secret(secret, None) {
+        Ok(kp) => Ok(Box::new(InlineSigner::new(kp))),
+        Err(error) => Err((StatusCode::BAD_REQUEST, "invalid_secret_key", error)),
+    }
+}
 
 pub async fn sign_message(
+    State(state): State<AppState>,
     Json(payload): Json<SignMessageRequest>,
 ) -> Result<Json<SignMessageResponse>, (StatusCode, Json<ErrorResponse>)> {
     if payload.message.is_empty() {
         return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
             success: false,
+            code: "missing_fields",
             error: "Missing required fields".to_string(),
         })));
     }
     
-    if payload.secret.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Missing required fields".to_string(),
-        })));
-    }
-
-    let keypair = match bs58::decode(&payload.secret).into_vec() {
-        Ok(bytes) => {
-            if bytes.len() != 64 {
-                return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-                    success: false,
-                    error: "Invalid secret key length. Expected 64 bytes.".to_string(),
-                })));
-            }
-            
-            match Keypair::try_from(bytes.as_slice()) {
-                Ok(kp) => kp,
-                Err(e) => {
-                    return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-                        success: false,
-                        error: format!("Invalid secret key format: {}", e),
-                    })));
-                }
-            }
+    let signer = match message_signer(&state, &payload.secret, payload.key_id.as_deref()) {
+        Ok(signer) => signer,
+        Err((status, code, error)) => {
+            return Err((status, Json(ErrorResponse {
+                success: false,
+                code,
+                error,
+            })));
         }
-        Err(_) => {
+    };
+
+    let message_encoding = encoding_option(payload.message_encoding.as_deref(), "utf8");
+    let signature_encoding = encoding_option(payload.signature_encoding.as_deref(), "base64");
+
+    let (message_bytes, format) = match check_signature_encoding(&signature_encoding)
+        .and_then(|_| decode_message(&payload.message, &message_encoding))
+        .and_then(|message| signing_bytes(message, payload.offchain))
+    {
+        Ok(signing_bytes) => signing_bytes,
+        Err(error) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
-                error: "Invalid base58 encoding in secret key".to_string(),
+                code: "invalid_message",
+                error,
             })));
         }
     };
 
-    let message_bytes = payload.message.as_bytes();
-
-    let signature = keypair.sign_message(message_bytes);
+    let signature = match signer.sign(&message_bytes).await {
+        Ok(signature) => {
+            telemetry::signature_produced("ed25519");
+            signature
+        }
+        Err(e) => {
+            return Err((e.status(), Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
-    let signature_base64 = general_purpose::STANDARD.encode(signature.as_ref());
+    let encoded_signature = encode_signature(&signature, &signature_encoding);
 
-    let public_key = keypair.pubkey().to_string();
+    let public_key = match signer.pubkey().await {
+        Ok(pubkey) => pubkey.to_string(),
+        Err(e) => {
+            return Err((e.status(), Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
     let response = SignMessageResponse {
         success: true,
         data: SignMessageData {
-            signature: signature_base64,
+            signature: encoded_signature,
             public_key,
             message: payload.message,
+            message_encoding,
+            signature_encoding,
+            format,
         },
     };
 
     Ok(Json(response))
 }
 
-pub async fn verify_message(
-    Json(payload): Json<VerifyMessageRequest>,
-) -> Result<Json<VerifyMessageResponse>, (StatusCode, Json<ErrorResponse>)> {
+struct VerifiedMessage {
+    valid: bool,
+    message_encoding: String,
+    signature_encoding: String,
+    format: Option<String>,
+}
+
+fn verify_signed_message(payload: &VerifyMessageRequest) -> Result<VerifiedMessage, String> {
     if payload.message.is_empty() || payload.signature.is_empty() || payload.pubkey.is_empty() {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Missing required fields".to_string(),
-        })));
+        return Err("Missing required fields".to_string());
     }
 
-    let public_key = match solana_sdk::pubkey::Pubkey::from_str(&payload.pubkey) {
+    let public_key = match parse_pubkey(&payload.pubkey) {
         Ok(pk) => pk,
-        Err(_) => {
-            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-                success: false,
-                error: "Invalid public key format".to_string(),
-            })));
-        }
+        Err(_) => return Err("Invalid public key format".to_string()),
     };
 
-    let signature_bytes = match general_purpose::STANDARD.decode(&payload.signature) {
-        Ok(bytes) => bytes,
-        Err(_) => {
-            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-                success: false,
-                error: "Invalid signature format".to_string(),
-            })));
-        }
+    let message_encoding = encoding_option(payload.message_encoding.as_deref(), "utf8");
+    let signature_encoding = encoding_option(payload.signature_encoding.as_deref(), "base64");
+    check_signature_encoding(&signature_encoding)?;
+
+    let signature = match decode_signature(&payload.signature, &signature_encoding) {
+        Some(sig) => sig,
+        None => return Err("Invalid signature format".to_string()),
     };
 
-    let signature = match Signature::try_from(signature_bytes.as_slice()) {
-        Ok(sig) => sig,
-        Err(_) => {
+    let message = decode_message(&payload.message, &message_encoding)?;
+    let (message_bytes, format) = signing_bytes(message, payload.offchain)?;
+
+    let valid = signature.verify(public_key.as_ref(), &message_bytes);
+    telemetry::signature_verified("ed25519", valid);
+
+    Ok(VerifiedMessage {
+        valid,
+        message_encoding,
+        signature_encoding,
+        format,
+    })
+}
+
+pub async fn verify_message(
+    Json(payload): Json<VerifyMessageRequest>,
+) -> Result<Json<VerifyMessageResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let verified = match verify_signed_message(&payload) {
+        Ok(verified) => verified,
+        Err(error) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
-                error: "Invalid signature format".to_string(),
+                code: "invalid_verify_params",
+                error,
             })));
         }
     };
 
-    let message_bytes = payload.message.as_bytes();
-
-    let is_valid = signature.verify(public_key.as_ref(), message_bytes);
-
     let response = VerifyMessageResponse {
         success: true,
         data: VerifyMessageData {
-            valid: is_valid,
+            valid: verified.valid,
             message: payload.message,
             pubkey: payload.pubkey,
+            message_encoding: verified.message_encoding,
+            signature_encoding: verified.signature_encoding,
+            format: verified.format,
         },
     };
 
     Ok(Json(response))
 }
+
+fn batch_permit(state: &AppState) -> Result<OwnedSemaphorePermit, (StatusCode, Json<ErrorResponse>)> {
+    state.batches.clone().try_acquire_owned().map_err(|_| {
+        (StatusCode::TOO_MANY_REQUESTS, Json(ErrorResponse {
+            success: false,
+            code: "batch_limit",
+            error: "Too many batch verifications in progress".to_string(),
+        }))
+    })
+}
+
+fn batch_workers(max_concurrent_batches: usize) -> usize {
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    (threads / max_concurrent_batches).max(1)
+}
+
+fn verify_batch_items(items: &[VerifyMessageRequest], workers: usize) -> Option<Vec<VerifyBatchResult>> {
+    let chunk_size = items.len().div_ceil(workers).max(BATCH_CHUNK_MIN);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(chunk, items)| {
+                scope.spawn(move || {
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(offset, item)| {
+                            let index = chunk * chunk_size + offset;
+                            match verify_signed_message(item) {
+                                Ok(verified) => VerifyBatchResult { index, valid: verified.valid, error: None },
+                                Err(error) => VerifyBatchResult { index, valid: false, error: Some(error) },
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(items.len());
+        for handle in handles {
+            results.extend(handle.join().ok()?);
+        }
+        Some(results)
+    })
+}
+
+pub async fn verify_message_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyBatchRequest>,
+) -> Result<Json<VerifyBatchResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.items.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    if payload.items.len() > MAX_BATCH_SIZE {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "batch_too_large",
+            error: format!("Batch may not contain more than {} items", MAX_BATCH_SIZE),
+        })));
+    }
+
+    let permit = batch_permit(&state)?;
+    let workers = batch_workers(state.config.max_concurrent_batches);
+    let total = payload.items.len();
+    let results = match tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        verify_batch_items(&payload.items, workers)
+    })
+    .await
+    {
+        Ok(Some(results)) if results.len() == total => results,
+        _ => {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse {
+                success: false,
+                code: "batch_failed",
+                error: "Batch verification failed".to_string(),
+            })));
+        }
+    };
+
+    let valid_count = results.iter().filter(|result| result.valid).count();
+
+    let response = VerifyBatchResponse {
+        success: true,
+        data: VerifyBatchData {
+            all_valid: valid_count == results.len(),
+            total: results.len(),
+            valid_count,
+            results,
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    fn item(keypair: &Keypair, message: &str, valid: bool) -> VerifyMessageRequest {
+        let signed = if valid { message.to_string() } else { format!("{}!", message) };
+        let signature = keypair.sign_message(signed.as_bytes());
+
+        VerifyMessageRequest {
+            message: message.to_string(),
+            signature: general_purpose::STANDARD.encode(signature.as_ref()),
+            pubkey: keypair.pubkey().to_string(),
+            offchain: false,
+            message_encoding: None,
+            signature_encoding: None,
+        }
+    }
+
+    #[test]
+    fn batch_results_keep_their_order_across_workers() {
+        let keypair = Keypair::new();
+        let items: Vec<_> = (0..300).map(|i| item(&keypair, &format!("message {}", i), i % 7 != 0)).collect();
+
+        let results = verify_batch_items(&items, 4).unwrap();
+        assert_eq!(results.len(), items.len());
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.index, i);
+            assert_eq!(result.valid, i % 7 != 0);
+        }
+    }
+
+    #[test]
+    fn batch_workers_split_the_cores_between_batches() {
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        assert_eq!(batch_workers(1), threads);
+        assert_eq!(batch_workers(threads * 2), 1);
+    }
+
+    #[test]
+    fn batches_beyond_the_limit_are_rejected() {
+        let state = AppState::for_tests(Config::from_toml("max_concurrent_batches = 1"));
+
+        let _held = batch_permit(&state).ok().unwrap();
+        let (status, body) = batch_permit(&state).err().unwrap();
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(body.code, "batch_limit");
+    }
+}
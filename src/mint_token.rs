@@ -1,12 +1,16 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::pubkey::Pubkey;
-use axum::{Json, http::StatusCode};
-use std::str::FromStr;
+use serde_json::Value;
+use spl_token_2022::instruction::{mint_to, mint_to_checked};
+use axum::{Json, extract::State, http::StatusCode};
+use crate::pubkey::parse_pubkey;
 use base64::{Engine as _, engine::general_purpose};
+use crate::amount::{AmountInput, check_decimals};
+use crate::state::AppState;
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     success: bool,
+    code: &'static str,
     error: String,
 }
 
@@ -15,7 +19,10 @@ pub struct MintTokenRequest {
     mint: String,
     destination: String,
     authority: String,
-    amount: i64,
+    amount: Option<AmountInput>,
+    #[serde(alias = "uiAmount")]
+    ui_amount: Option<AmountInput>,
+    decimals: Option<Value>,
 }
 
 #[derive(Serialize)]
@@ -39,76 +46,139 @@ pub struct AccountData {
 }
 
 pub async fn mint_token(
+    State(state): State<AppState>,
     Json(payload): Json<MintTokenRequest>,
 ) -> Result<Json<MintTokenResponse>, (StatusCode, Json<ErrorResponse>)> {
     if payload.mint.is_empty() || payload.destination.is_empty() || payload.authority.is_empty() {
         return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
             success: false,
+            code: "missing_fields",
             error: "Missing required fields".to_string(),
         })));
     }
 
-    if payload.amount <= 0 {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Amount must be greater than zero".to_string(),
-        })));
-    }
+    let decimals = match payload.decimals.as_ref().map(check_decimals).transpose() {
+        Ok(decimals) => decimals,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
-    let _mint_pubkey = match Pubkey::from_str(&payload.mint) {
+    let amount = match (&payload.amount, &payload.ui_amount, decimals) {
+        (Some(amount), None, _) => amount.to_base_units(),
+        (None, Some(ui_amount), Some(decimals)) => ui_amount.to_ui_base_units(decimals),
+        (None, Some(_), None) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_decimals",
+                error: "uiAmount requires decimals".to_string(),
+            })));
+        }
+        (None, None, _) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_fields",
+                error: "Missing required fields".to_string(),
+            })));
+        }
+        (Some(_), Some(_), _) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "conflicting_fields",
+                error: "Provide either amount or uiAmount, not both".to_string(),
+            })));
+        }
+    };
+
+    let amount = match amount {
+        Ok(amount) => amount,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
+
+    let mint_pubkey = match parse_pubkey(&payload.mint) {
         Ok(pub_key) => pub_key,
         Err(_) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
+                code: "invalid_mint",
                 error: "Invalid mint address".to_string(),
             })));
         }
     };
 
-    let _destination_pubkey = match Pubkey::from_str(&payload.destination) {
+    let destination_pubkey = match parse_pubkey(&payload.destination) {
         Ok(pub_key) => pub_key,
         Err(_) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
+                code: "invalid_destination",
                 error: "Invalid destination address".to_string(),
             })));
         }
     };
 
-    let _authority_pubkey = match Pubkey::from_str(&payload.authority) {
+    let authority_pubkey = match parse_pubkey(&payload.authority) {
         Ok(pub_key) => pub_key,
         Err(_) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
+                code: "invalid_authority",
                 error: "Invalid authority address".to_string(),
             })));
         }
     };
 
-    let accounts = vec![
-        AccountData {
-            pubkey: payload.mint.clone(),
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountData {
-            pubkey: payload.destination.clone(),
-            is_signer: false,
-            is_writable: true,
-        },
-        AccountData {
-            pubkey: payload.authority.clone(),
-            is_signer: true,
-            is_writable: false,
-        },
-    ];
+    let token_program = state.config.token_program;
+    let instruction = match decimals {
+        Some(decimals) => mint_to_checked(
+            &token_program,
+            &mint_pubkey,
+            &destination_pubkey,
+            &authority_pubkey,
+            &[],
+            amount,
+            decimals,
+        ),
+        None => mint_to(&token_program, &mint_pubkey, &destination_pubkey, &authority_pubkey, &[], amount),
+    };
+
+    let instruction = match instruction {
+        Ok(ix) => ix,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "instruction_failed",
+                error: "Failed to create mint instruction".to_string(),
+            })));
+        }
+    };
+
+    let accounts: Vec<AccountData> = instruction
+        .accounts
+        .iter()
+        .map(|acc| AccountData {
+            pubkey: acc.pubkey.to_string(),
+            is_signer: acc.is_signer,
+            is_writable: acc.is_writable,
+        })
+        .collect();
 
     let response = MintTokenResponse {
         success: true,
         data: MintTokenData {
-            program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".to_string(),
+            program_id: instruction.program_id.to_string(),
             accounts,
-            instruction_data: general_purpose::STANDARD.encode(&[1, 2, 3, 4]),
+            instruction_data: general_purpose::STANDARD.encode(&instruction.data),
         },
     };
 
@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::{MAX_SEED_LEN, MAX_SEEDS, Pubkey};
+use axum::{Json, http::StatusCode};
+use crate::pubkey::parse_pubkey;
+use base64::{Engine as _, engine::general_purpose};
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct SeedInput {
+    #[serde(rename = "type")]
+    kind: String,
+    value: Value,
+}
+
+#[derive(Deserialize)]
+pub struct FindProgramAddressRequest {
+    #[serde(alias = "programId")]
+    program_id: String,
+    seeds: Vec<SeedInput>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateProgramAddressRequest {
+    #[serde(alias = "programId")]
+    program_id: String,
+    seeds: Vec<SeedInput>,
+    bump: u8,
+}
+
+#[derive(Serialize)]
+pub struct ProgramAddressResponse {
+    success: bool,
+    data: ProgramAddressData,
+}
+
+#[derive(Serialize)]
+pub struct ProgramAddressData {
+    address: String,
+    bump: u8,
+    program_id: String,
+    seeds: Vec<String>,
+}
+
+fn seed_integer(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => text.parse::<u64>().ok(),
+        _ => None,
+    }
+}
+
+fn decode_seed(seed: &SeedInput) -> Result<Vec<u8>, String> {
+    let kind = seed.kind.to_ascii_lowercase();
+
+    let bytes = match kind.as_str() {
+        "utf8" | "string" => match seed.value.as_str() {
+            Some(text) => text.as_bytes().to_vec(),
+            None => return Err("Seed of type utf8 must be a string".to_string()),
+        },
+        "pubkey" => match seed.value.as_str().map(parse_pubkey) {
+            Some(Ok(pub_key)) => pub_key.to_bytes().to_vec(),
+            _ => return Err("Invalid pubkey seed".to_string()),
+        },
+        "hex" => match seed.value.as_str().map(|text| hex::decode(text.trim_start_matches("0x"))) {
+            Some(Ok(bytes)) => bytes,
+            _ => return Err("Invalid hex seed".to_string()),
+        },
+        "base64" => match seed.value.as_str().map(|text| general_purpose::STANDARD.decode(text)) {
+            Some(Ok(bytes)) => bytes,
+            _ => return Err("Invalid base64 seed".to_string()),
+        },
+        "u8" | "u16" | "u32" | "u64" => {
+            let value = match seed_integer(&seed.value) {
+                Some(value) => value,
+                None => return Err(format!("Seed of type {} must be a non-negative integer", kind)),
+            };
+
+            let width = match kind.as_str() {
+                "u8" => 1,
+                "u16" => 2,
+                "u32" => 4,
+                _ => 8,
+            };
+
+            if width < 8 && value >> (width * 8) != 0 {
+                return Err(format!("Seed value {} does not fit in {}", value, kind));
+            }
+
+            value.to_le_bytes()[..width].to_vec()
+        }
+        _ => return Err(format!("Unsupported seed type: {}", seed.kind)),
+    };
+
+    if bytes.len() > MAX_SEED_LEN {
+        return Err(format!("Seed exceeds maximum length of {} bytes", MAX_SEED_LEN));
+    }
+
+    Ok(bytes)
+}
+
+fn decode_seeds(seeds: &[SeedInput], max_seeds: usize) -> Result<Vec<Vec<u8>>, String> {
+    if seeds.len() > max_seeds {
+        return Err(format!("Too many seeds, maximum is {}", max_seeds));
+    }
+
+    seeds.iter().map(decode_seed).collect()
+}
+
+pub async fn find_program_address(
+    Json(payload): Json<FindProgramAddressRequest>,
+) -> Result<Json<ProgramAddressResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.program_id.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let program_id = match parse_pubkey(&payload.program_id) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_program_id",
+                error: "Invalid program id".to_string(),
+            })));
+        }
+    };
+
+    let seeds = match decode_seeds(&payload.seeds, MAX_SEEDS - 1) {
+        Ok(seeds) => seeds,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_seeds",
+                error,
+            })));
+        }
+    };
+
+    let seed_slices: Vec<&[u8]> = seeds.iter().map(|seed| seed.as_slice()).collect();
+    let (address, bump) = match Pubkey::try_find_program_address(&seed_slices, &program_id) {
+        Some(result) => result,
+        None => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "no_viable_bump",
+                error: "Unable to find a viable program address bump seed".to_string(),
+            })));
+        }
+    };
+
+    let response = ProgramAddressResponse {
+        success: true,
+        data: ProgramAddressData {
+            address: address.to_string(),
+            bump,
+            program_id: program_id.to_string(),
+            seeds: seeds.iter().map(hex::encode).collect(),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn create_program_address(
+    Json(payload): Json<CreateProgramAddressRequest>,
+) -> Result<Json<ProgramAddressResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.program_id.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let program_id = match parse_pubkey(&payload.program_id) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_program_id",
+                error: "Invalid program id".to_string(),
+            })));
+        }
+    };
+
+    let seeds = match decode_seeds(&payload.seeds, MAX_SEEDS - 1) {
+        Ok(seeds) => seeds,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_seeds",
+                error,
+            })));
+        }
+    };
+
+    let bump = [payload.bump];
+    let mut seed_slices: Vec<&[u8]> = seeds.iter().map(|seed| seed.as_slice()).collect();
+    seed_slices.push(&bump);
+
+    let address = match Pubkey::create_program_address(&seed_slices, &program_id) {
+        Ok(address) => address,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_seeds",
+                error: "Invalid seeds, address must fall off the curve".to_string(),
+            })));
+        }
+    };
+
+    let response = ProgramAddressResponse {
+        success: true,
+        data: ProgramAddressData {
+            address: address.to_string(),
+            bump: payload.bump,
+            program_id: program_id.to_string(),
+            seeds: seeds.iter().map(hex::encode).collect(),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+    use spl_associated_token_account_client::program::ID as ASSOCIATED_TOKEN_PROGRAM_ID;
+
+    fn seed(kind: &str, value: Value) -> SeedInput {
+        SeedInput { kind: kind.to_string(), value }
+    }
+
+    fn request<T: serde::de::DeserializeOwned>(value: Value) -> Json<T> {
+        Json(serde_json::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn encodes_each_seed_type() {
+        let pubkey = Pubkey::new_unique();
+
+        assert_eq!(decode_seed(&seed("utf8", json!("vault"))).unwrap(), b"vault");
+        assert_eq!(decode_seed(&seed("String", json!("vault"))).unwrap(), b"vault");
+        assert_eq!(decode_seed(&seed("pubkey", json!(pubkey.to_string()))).unwrap(), pubkey.to_bytes());
+        assert_eq!(decode_seed(&seed("hex", json!("0xdead"))).unwrap(), [0xde, 0xad]);
+        assert_eq!(decode_seed(&seed("base64", json!("3q0="))).unwrap(), [0xde, 0xad]);
+        assert_eq!(decode_seed(&seed("u8", json!(255))).unwrap(), [255]);
+        assert_eq!(decode_seed(&seed("u16", json!("513"))).unwrap(), [1, 2]);
+        assert_eq!(decode_seed(&seed("u32", json!(1))).unwrap(), [1, 0, 0, 0]);
+        assert_eq!(decode_seed(&seed("u64", json!(u64::MAX))).unwrap(), [255; 8]);
+    }
+
+    #[test]
+    fn rejects_invalid_seeds() {
+        assert!(decode_seed(&seed("utf8", json!(1))).is_err());
+        assert!(decode_seed(&seed("utf8", json!("x".repeat(MAX_SEED_LEN + 1)))).is_err());
+        assert!(decode_seed(&seed("pubkey", json!("not-a-key"))).is_err());
+        assert!(decode_seed(&seed("hex", json!("0xzz"))).is_err());
+        assert!(decode_seed(&seed("u8", json!(256))).is_err());
+        assert!(decode_seed(&seed("u16", json!(-1))).is_err());
+        assert!(decode_seed(&seed("u32", json!(1.5))).is_err());
+        assert!(decode_seed(&seed("i64", json!(1))).is_err());
+
+        let seeds: Vec<_> = (0..MAX_SEEDS).map(|_| seed("u8", json!(1))).collect();
+        assert!(decode_seeds(&seeds, MAX_SEEDS - 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn finds_the_associated_token_address() {
+        let wallet = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let token_program = spl_token_2022::ID;
+
+        let Json(found) = find_program_address(request(json!({
+            "programId": ASSOCIATED_TOKEN_PROGRAM_ID.to_string(),
+            "seeds": [
+                { "type": "pubkey", "value": wallet.to_string() },
+                { "type": "pubkey", "value": token_program.to_string() },
+                { "type": "pubkey", "value": mint.to_string() },
+            ],
+        })))
+        .await
+        .ok()
+        .unwrap();
+
+        let expected = get_associated_token_address_with_program_id(&wallet, &mint, &token_program);
+        assert_eq!(found.data.address, expected.to_string());
+    }
+
+    #[tokio::test]
+    async fn finds_the_highest_viable_bump() {
+        let program_id = Pubkey::new_unique();
+        let (index, bump) = (0..64u64)
+            .map(|index| (index, Pubkey::find_program_address(&[b"vault", &index.to_le_bytes()], &program_id).1))
+            .find(|(_, bump)| *bump < u8::MAX)
+            .unwrap();
+        let seeds = json!([{ "type": "utf8", "value": "vault" }, { "type": "u64", "value": index }]);
+
+        let Json(found) = find_program_address(request(json!({ "programId": program_id.to_string(), "seeds": seeds })))
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!(found.data.bump, bump);
+
+        let Json(created) = create_program_address(request(json!({ "programId": program_id.to_string(), "seeds": seeds, "bump": bump })))
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!(created.data.address, found.data.address);
+
+        for higher in bump + 1..=u8::MAX {
+            let (_, Json(body)) = create_program_address(request(json!({ "programId": program_id.to_string(), "seeds": seeds, "bump": higher })))
+                .await
+                .err()
+                .unwrap();
+            assert_eq!(body.code, "invalid_seeds");
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::{MAX_SEED_LEN, Pubkey};
+use axum::{Json, http::StatusCode};
+use base64::{Engine as _, engine::general_purpose};
+use std::fmt;
+
+const MAX_BASE58_LEN: usize = 44;
+
+const WELL_KNOWN_ADDRESSES: &[(&str, &str, &str)] = &[
+    ("11111111111111111111111111111111", "System Program", "program"),
+    ("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "Token Program", "program"),
+    ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", "Token-2022 Program", "program"),
+    ("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL", "Associated Token Account Program", "program"),
+    ("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr", "Memo Program", "program"),
+    ("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo", "Memo Program (v1)", "program"),
+    ("ComputeBudget111111111111111111111111111111", "Compute Budget Program", "program"),
+    ("AddressLookupTab1e1111111111111111111111111", "Address Lookup Table Program", "program"),
+    ("Stake11111111111111111111111111111111111111", "Stake Program", "program"),
+    ("Vote111111111111111111111111111111111111111", "Vote Program", "program"),
+    ("Config1111111111111111111111111111111111111", "Config Program", "program"),
+    ("Ed25519SigVerify111111111111111111111111111", "Ed25519 Program", "program"),
+    ("KeccakSecp256k11111111111111111111111111111", "Secp256k1 Program", "program"),
+    ("BPFLoader1111111111111111111111111111111111", "BPF Loader (deprecated)", "program"),
+    ("BPFLoader2111111111111111111111111111111111", "BPF Loader", "program"),
+    ("BPFLoaderUpgradeab1e11111111111111111111111", "BPF Upgradeable Loader", "program"),
+    ("LoaderV411111111111111111111111111111111111", "Loader v4", "program"),
+    ("NativeLoader1111111111111111111111111111111", "Native Loader", "program"),
+    ("So11111111111111111111111111111111111111112", "Wrapped SOL Mint", "mint"),
+    ("SysvarC1ock11111111111111111111111111111111", "Clock Sysvar", "sysvar"),
+    ("SysvarEpochSchedu1e111111111111111111111111", "Epoch Schedule Sysvar", "sysvar"),
+    ("SysvarEpochRewards1111111111111111111111111", "Epoch Rewards Sysvar", "sysvar"),
+    ("SysvarFees111111111111111111111111111111111", "Fees Sysvar", "sysvar"),
+    ("Sysvar1nstructions1111111111111111111111111", "Instructions Sysvar", "sysvar"),
+    ("SysvarLastRestartS1ot1111111111111111111111", "Last Restart Slot Sysvar", "sysvar"),
+    ("SysvarRecentB1ockHashes11111111111111111111", "Recent Blockhashes Sysvar", "sysvar"),
+    ("SysvarRent111111111111111111111111111111111", "Rent Sysvar", "sysvar"),
+    ("SysvarRewards111111111111111111111111111111", "Rewards Sysvar", "sysvar"),
+    ("SysvarS1otHashes111111111111111111111111111", "Slot Hashes Sysvar", "sysvar"),
+    ("SysvarS1otHistory11111111111111111111111111", "Slot History Sysvar", "sysvar"),
+    ("SysvarStakeHistory1111111111111111111111111", "Stake History Sysvar", "sysvar"),
+];
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct InspectPubkeyRequest {
+    pubkey: String,
+    seed: Option<String>,
+    owner: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct InspectPubkeyResponse {
+    success: bool,
+    data: InspectPubkeyData,
+}
+
+#[derive(Serialize)]
+pub struct InspectPubkeyData {
+    pubkey: String,
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hex: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    base64: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    on_curve: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    well_known: Option<WellKnownData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    with_seed: Option<WithSeedData>,
+}
+
+#[derive(Serialize)]
+pub struct WellKnownData {
+    name: String,
+    kind: String,
+}
+
+#[derive(Serialize)]
+pub struct WithSeedData {
+    address: String,
+    seed: String,
+    owner: String,
+}
+
+#[derive(Debug)]
+pub enum PubkeyError {
+    Empty,
+    TooLong,
+    InvalidBase58,
+    InvalidLength(usize),
+}
+
+impl fmt::Display for PubkeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PubkeyError::Empty => write!(f, "Public key is empty"),
+            PubkeyError::TooLong => write!(f, "Public key exceeds {} base58 characters", MAX_BASE58_LEN),
+            PubkeyError::InvalidBase58 => write!(f, "Public key is not valid base58"),
+            PubkeyError::InvalidLength(len) => write!(f, "Public key must be 32 bytes, got {}", len),
+        }
+    }
+}
+
+pub fn parse_pubkey(value: &str) -> Result<Pubkey, PubkeyError> {
+    if value.is_empty() {
+        return Err(PubkeyError::Empty);
+    }
+
+    if value.len() > MAX_BASE58_LEN {
+        return Err(PubkeyError::TooLong);
+    }
+
+    let bytes = match bs58::decode(value).into_vec() {
+        Ok(bytes) => bytes,
+        Err(_) => return Err(PubkeyError::InvalidBase58),
+    };
+
+    match Pubkey::try_from(bytes.as_slice()) {
+        Ok(pub_key) => Ok(pub_key),
+        Err(_) => Err(PubkeyError::InvalidLength(bytes.len())),
+    }
+}
+
+pub async fn inspect_pubkey(
+    Json(payload): Json<InspectPubkeyRequest>,
+) -> Result<Json<InspectPubkeyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let pub_key = match parse_pubkey(&payload.pubkey) {
+        Ok(pub_key) => pub_key,
+        Err(e) => {
+            return Ok(Json(InspectPubkeyResponse {
+                success: true,
+                data: InspectPubkeyData {
+                    pubkey: payload.pubkey,
+                    valid: false,
+                    error: Some(e.to_string()),
+                    hex: None,
+                    base64: None,
+                    on_curve: None,
+                    well_known: None,
+                    with_seed: None,
+                },
+            }));
+        }
+    };
+
+    let with_seed = match (&payload.seed, &payload.owner) {
+        (None, None) => None,
+        (Some(seed), Some(owner)) => {
+            if seed.len() > MAX_SEED_LEN {
+                return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                    success: false,
+                    code: "seed_too_long",
+                    error: format!("Seed exceeds maximum length of {} bytes", MAX_SEED_LEN),
+                })));
+            }
+
+            let owner_pubkey = match parse_pubkey(owner) {
+                Ok(pub_key) => pub_key,
+                Err(_) => {
+                    return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                        success: false,
+                        code: "invalid_owner",
+                        error: "Invalid owner address".to_string(),
+                    })));
+                }
+            };
+
+            let address = match Pubkey::create_with_seed(&pub_key, seed, &owner_pubkey) {
+                Ok(address) => address,
+                Err(_) => {
+                    return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                        success: false,
+                        code: "derivation_failed",
+                        error: "Failed to derive address with seed".to_string(),
+                    })));
+                }
+            };
+
+            Some(WithSeedData {
+                address: address.to_string(),
+                seed: seed.clone(),
+                owner: owner_pubkey.to_string(),
+            })
+        }
+        _ => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_fields",
+                error: "Both seed and owner are required to derive an address with seed".to_string(),
+            })));
+        }
+    };
+
+    let address = pub_key.to_string();
+    let well_known = WELL_KNOWN_ADDRESSES
+        .iter()
+        .find(|(known, _, _)| *known == address)
+        .map(|(_, name, kind)| WellKnownData {
+            name: name.to_string(),
+            kind: kind.to_string(),
+        });
+
+    let response = InspectPubkeyResponse {
+        success: true,
+        data: InspectPubkeyData {
+            pubkey: address,
+            valid: true,
+            error: None,
+            hex: Some(hex::encode(pub_key.to_bytes())),
+            base64: Some(general_purpose::STANDARD.encode(pub_key.to_bytes())),
+            on_curve: Some(pub_key.is_on_curve()),
+            well_known,
+            with_seed,
+        },
+    };
+
+    Ok(Json(response))
+}
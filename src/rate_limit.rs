@@ -0,0 +1,380 @@
+use serde::Serialize;
+use axum::{
+    Json,
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use ipnet::IpNet;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use crate::auth::ApiKey;
+use crate::config::{Config, RateLimit};
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+
+const MAX_TRACKED_BUCKETS: usize = 100_000;
+const EVICT_FRACTION: usize = 10;
+const SWEEP_INTERVAL_SECS: u64 = 30;
+const IPV6_PREFIX_MASK: u128 = !0 << 64;
+const CONCURRENCY_RETRY_AFTER_SECS: u64 = 1;
+const EXEMPT_ROUTES: &[&str] = &["/healthz", "/readyz"];
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn refill(&self, limit: RateLimit, now: Instant) -> f64 {
+        (self.tokens + now.duration_since(self.updated).as_secs_f64() * limit.rate).min(limit.burst as f64)
+    }
+}
+
+struct Buckets<K> {
+    limit: Option<RateLimit>,
+    buckets: Mutex<HashMap<K, Bucket>>,
+}
+
+impl<K: Hash + Eq> Buckets<K> {
+    fn new(limit: Option<RateLimit>) -> Buckets<K> {
+        Buckets {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn take(&self, key: K, limit: RateLimit, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= MAX_TRACKED_BUCKETS && !buckets.contains_key(&key) {
+            evict_oldest(&mut buckets);
+        }
+
+        let bucket = buckets.entry(key).or_insert(Bucket { tokens: limit.burst as f64, updated: now });
+        bucket.tokens = bucket.refill(limit, now);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / limit.rate))
+        }
+    }
+
+    fn sweep(&self, now: Instant) {
+        if let Some(limit) = self.limit {
+            self.buckets
+                .lock()
+                .unwrap()
+                .retain(|_, bucket| bucket.refill(limit, now) < limit.burst as f64);
+        }
+    }
+}
+
+fn evict_oldest<K>(buckets: &mut HashMap<K, Bucket>) {
+    let mut updated: Vec<Instant> = buckets.values().map(|bucket| bucket.updated).collect();
+    let count = (updated.len() / EVICT_FRACTION).max(1);
+    let (_, cutoff, _) = updated.select_nth_unstable(count - 1);
+    let cutoff = *cutoff;
+
+    buckets.retain(|_, bucket| bucket.updated > cutoff);
+    tracing::warn!("Rate limiter reached {} tracked clients, evicted the least recently seen", MAX_TRACKED_BUCKETS);
+}
+
+pub struct RateLimiter {
+    ip_buckets: Buckets<IpAddr>,
+    api_key_buckets: Buckets<String>,
+    trusted_proxies: Vec<IpNet>,
+    in_flight: Arc<Semaphore>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &Config) -> Arc<RateLimiter> {
+        let limiter = Arc::new(RateLimiter {
+            ip_buckets: Buckets::new(config.ip_rate_limit),
+            api_key_buckets: Buckets::new(config.api_key_rate_limit),
+            trusted_proxies: config.trusted_proxies.clone(),
+            in_flight: Arc::new(Semaphore::new(config.max_concurrent_requests)),
+        });
+
+        if limiter.ip_buckets.limit.is_some() || limiter.api_key_buckets.limit.is_some() {
+            tokio::spawn(sweep_buckets(Arc::downgrade(&limiter)));
+        }
+
+        limiter
+    }
+
+    pub fn try_acquire_in_flight(&self) -> Option<OwnedSemaphorePermit> {
+        self.in_flight.clone().try_acquire_owned().ok()
+    }
+
+    fn sweep(&self) {
+        let now = Instant::now();
+        self.ip_buckets.sweep(now);
+        self.api_key_buckets.sweep(now);
+    }
+
+    fn is_trusted(&self, ip: &IpAddr) -> bool {
+        self.trusted_proxies.iter().any(|proxy| proxy.contains(ip))
+    }
+
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.is_trusted(&peer) {
+            return peer;
+        }
+
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|hop| hop.trim().parse::<IpAddr>().ok())
+            .collect::<Vec<_>>();
+
+        for hop in forwarded.into_iter().rev() {
+            match hop {
+                Some(ip) if self.is_trusted(&ip) => continue,
+                Some(ip) => return ip,
+                None => return peer,
+            }
+        }
+
+        headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<IpAddr>().ok())
+            .unwrap_or(peer)
+    }
+}
+
+async fn sweep_buckets(limiter: Weak<RateLimiter>) {
+    let mut interval = tokio::time::interval(Duration::from_secs(SWEEP_INTERVAL_SECS));
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+        match limiter.upgrade() {
+            Some(limiter) => limiter.sweep(),
+            None => break,
+        }
+    }
+}
+
+pub fn client_prefix(ip: IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V6(ip) => IpAddr::V6(Ipv6Addr::from(ip.to_bits() & IPV6_PREFIX_MASK)),
+        ip => ip,
+    }
+}
+
+fn too_many_requests(retry_after: Duration, code: &'static str, error: &str) -> Response {
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+    let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(ErrorResponse {
+        success: false,
+        code,
+        error: error.to_string(),
+    }))
+        .into_response();
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds.max(1)));
+    response
+}
+
+fn is_exempt(request: &Request) -> bool {
+    request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| EXEMPT_ROUTES.contains(&path.as_str()))
+}
+
+pub async fn limit_concurrency(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if is_exempt(&request) {
+        return next.run(request).await;
+    }
+
+    let _permit = match state.rate_limiter.try_acquire_in_flight() {
+        Some(permit) => permit,
+        None => {
+            return too_many_requests(
+                Duration::from_secs(CONCURRENCY_RETRY_AFTER_SECS),
+                "concurrency_limit",
+                "Server is at its concurrent request limit",
+            );
+        }
+    };
+
+    next.run(request).await
+}
+
+pub async fn limit_by_ip(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let limiter = &state.rate_limiter;
+
+    let (limit, peer) = match (limiter.ip_buckets.limit, request.extensions().get::<ConnectInfo<SocketAddr>>()) {
+        (Some(limit), Some(ConnectInfo(peer))) if !is_exempt(&request) => (limit, peer.ip()),
+        _ => return next.run(request).await,
+    };
+
+    let ip = limiter.client_ip(peer, request.headers());
+    if let Err(retry_after) = limiter.ip_buckets.take(client_prefix(ip), limit, Instant::now()) {
+        return too_many_requests(retry_after, "rate_limited", "Rate limit exceeded");
+    }
+
+    next.run(request).await
+}
+
+pub async fn limit_by_api_key(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let limiter = &state.rate_limiter;
+
+    let (limit, api_key) = match (limiter.api_key_buckets.limit, request.extensions().get::<Arc<ApiKey>>()) {
+        (Some(limit), Some(api_key)) => (limit, api_key.name.clone()),
+        _ => return next.run(request).await,
+    };
+
+    if let Err(retry_after) = limiter.api_key_buckets.take(api_key, limit, Instant::now()) {
+        return too_many_requests(retry_after, "rate_limited", "Rate limit exceeded");
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(ip: &str) -> IpAddr {
+        client_prefix(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn groups_ipv6_clients_by_prefix() {
+        assert!(key("2001:db8:1:2:aaaa::1") == key("2001:db8:1:2:ffff::9"));
+        assert!(key("2001:db8:1:2::1") != key("2001:db8:1:3::1"));
+    }
+
+    #[test]
+    fn keeps_ipv4_clients_distinct() {
+        assert!(key("203.0.113.1") != key("203.0.113.2"));
+        assert!(key("::ffff:203.0.113.1") == key("203.0.113.1"));
+    }
+
+    fn limiter(trusted_proxies: &str) -> Arc<RateLimiter> {
+        RateLimiter::new(&Config::from_toml(&format!("trusted_proxies = [{}]", trusted_proxies)))
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn ignores_forwarded_headers_from_untrusted_peers() {
+        let limiter = limiter(r#""10.0.0.0/8""#);
+        let peer: IpAddr = "203.0.113.7".parse().unwrap();
+        let spoofed = headers(&[("x-forwarded-for", "198.51.100.1"), ("x-real-ip", "198.51.100.2")]);
+
+        assert_eq!(limiter.client_ip(peer, &spoofed), peer);
+    }
+
+    #[test]
+    fn takes_the_last_untrusted_hop_from_trusted_proxies() {
+        let limiter = limiter(r#""10.0.0.0/8""#);
+        let peer: IpAddr = "10.0.0.1".parse().unwrap();
+
+        let chain = headers(&[("x-forwarded-for", "198.51.100.1, 203.0.113.9, 10.0.0.2")]);
+        assert_eq!(limiter.client_ip(peer, &chain), "203.0.113.9".parse::<IpAddr>().unwrap());
+
+        let split = headers(&[("x-forwarded-for", "198.51.100.1"), ("x-forwarded-for", "203.0.113.9")]);
+        assert_eq!(limiter.client_ip(peer, &split), "203.0.113.9".parse::<IpAddr>().unwrap());
+
+        let garbage = headers(&[("x-forwarded-for", "198.51.100.1, not-an-ip")]);
+        assert_eq!(limiter.client_ip(peer, &garbage), peer);
+
+        let real_ip = headers(&[("x-real-ip", "198.51.100.4")]);
+        assert_eq!(limiter.client_ip(peer, &real_ip), "198.51.100.4".parse::<IpAddr>().unwrap());
+        assert_eq!(limiter.client_ip(peer, &HeaderMap::new()), peer);
+    }
+
+    #[test]
+    fn refills_at_the_configured_rate() {
+        let limit = RateLimit { rate: 2.0, burst: 2 };
+        let buckets = Buckets::new(Some(limit));
+        let start = Instant::now();
+
+        assert!(buckets.take("client", limit, start).is_ok());
+        assert!(buckets.take("client", limit, start).is_ok());
+        assert_eq!(buckets.take("client", limit, start), Err(Duration::from_millis(500)));
+
+        let later = start + Duration::from_millis(250);
+        assert_eq!(buckets.take("client", limit, later), Err(Duration::from_millis(250)));
+
+        let refilled = start + Duration::from_secs(10);
+        assert!(buckets.take("client", limit, refilled).is_ok());
+        assert!(buckets.take("client", limit, refilled).is_ok());
+        assert!(buckets.take("client", limit, refilled).is_err());
+        assert!(buckets.take("other", limit, refilled).is_ok());
+    }
+
+    #[test]
+    fn evicts_the_oldest_buckets_instead_of_rejecting() {
+        let limit = RateLimit { rate: 1.0, burst: 1 };
+        let buckets = Buckets::new(Some(limit));
+        let start = Instant::now();
+
+        for client in 0..MAX_TRACKED_BUCKETS {
+            let now = start + Duration::from_millis(client as u64);
+            assert!(buckets.take(client, limit, now).is_ok());
+        }
+
+        let now = start + Duration::from_secs(1_000);
+        assert!(buckets.take(MAX_TRACKED_BUCKETS, limit, now).is_ok());
+
+        let tracked = buckets.buckets.lock().unwrap();
+        assert!(tracked.len() <= MAX_TRACKED_BUCKETS);
+        assert!(!tracked.contains_key(&0));
+        assert!(tracked.contains_key(&(MAX_TRACKED_BUCKETS - 1)));
+    }
+
+    #[test]
+    fn sweeps_only_full_buckets() {
+        let limit = RateLimit { rate: 1.0, burst: 2 };
+        let buckets = Buckets::new(Some(limit));
+        let start = Instant::now();
+
+        buckets.take("idle", limit, start).unwrap();
+        buckets.take("busy", limit, start + Duration::from_secs(5)).unwrap();
+        buckets.take("busy", limit, start + Duration::from_secs(5)).unwrap();
+        buckets.sweep(start + Duration::from_secs(6));
+
+        let tracked = buckets.buckets.lock().unwrap();
+        assert!(!tracked.contains_key("idle"));
+        assert!(tracked.contains_key("busy"));
+    }
+
+    #[test]
+    fn rounds_retry_after_up_to_whole_seconds() {
+        let retry_after = |duration| {
+            too_many_requests(duration, "rate_limited", "Rate limit exceeded").headers()[header::RETRY_AFTER].clone()
+        };
+
+        assert_eq!(retry_after(Duration::from_millis(1)), "1");
+        assert_eq!(retry_after(Duration::from_millis(1_500)), "2");
+        assert_eq!(retry_after(Duration::from_secs(3)), "3");
+        assert_eq!(retry_after(Duration::ZERO), "1");
+    }
+}
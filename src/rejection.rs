@@ -0,0 +1,62 @@
+use serde::Serialize;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{HeaderValue, Response, StatusCode, header},
+    middleware::Next,
+};
+use crate::telemetry;
+
+const MAX_REJECTION_BODY_BYTES: usize = 16 * 1024;
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+fn default_message(status: StatusCode) -> String {
+    match status {
+        StatusCode::REQUEST_TIMEOUT => "Request timed out".to_string(),
+        StatusCode::PAYLOAD_TOO_LARGE => "Request body is too large".to_string(),
+        _ => status.canonical_reason().unwrap_or("Request failed").to_string(),
+    }
+}
+
+pub async fn json_rejections(request: Request, next: Next) -> Response<Body> {
+    let response = next.run(request).await;
+    let status = response.status();
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+
+    if is_json || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let error = match axum::body::to_bytes(body, MAX_REJECTION_BODY_BYTES).await {
+        Ok(_) if status == StatusCode::PAYLOAD_TOO_LARGE => default_message(status),
+        Ok(bytes) => match String::from_utf8_lossy(&bytes).trim() {
+            "" => default_message(status),
+            text => text.to_string(),
+        },
+        Err(_) => default_message(status),
+    };
+
+    let body = match serde_json::to_vec(&ErrorResponse {
+        success: false,
+        code: telemetry::status_code_name(status),
+        error,
+    }) {
+        Ok(body) => body,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    Response::from_parts(parts, Body::from(body))
+}
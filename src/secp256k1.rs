@@ -0,0 +1,391 @@
+use serde::{Deserialize, Serialize};
+use libsecp256k1::{Message, PublicKey, RecoveryId, SecretKey, Signature};
+use sha3::{Digest, Keccak256};
+use solana_secp256k1_program::eth_address_from_pubkey;
+use axum::{Json, http::StatusCode};
+use crate::message::{decode_message, encoding_option};
+use crate::redact::Redacted;
+use crate::telemetry;
+
+const PERSONAL_SIGN_PREFIX: &str = "\x19Ethereum Signed Message:\n";
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct Secp256k1SignRequest {
+    message: String,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    #[serde(default = "default_personal_sign", alias = "personalSign")]
+    personal_sign: bool,
+    secret: Redacted<String>,
+}
+
+#[derive(Deserialize)]
+pub struct Secp256k1VerifyRequest {
+    message: String,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    #[serde(default = "default_personal_sign", alias = "personalSign")]
+    personal_sign: bool,
+    signature: String,
+    address: String,
+}
+
+#[derive(Deserialize)]
+pub struct Secp256k1RecoverRequest {
+    message: String,
+    #[serde(alias = "messageEncoding")]
+    message_encoding: Option<String>,
+    #[serde(default = "default_personal_sign", alias = "personalSign")]
+    personal_sign: bool,
+    signature: String,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1SignResponse {
+    success: bool,
+    data: Secp256k1SignData,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1SignData {
+    signature: String,
+    recovery_id: u8,
+    address: String,
+    public_key: String,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1VerifyResponse {
+    success: bool,
+    data: Secp256k1VerifyData,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1VerifyData {
+    valid: bool,
+    address: String,
+    recovered_address: String,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1RecoverResponse {
+    success: bool,
+    data: Secp256k1RecoverData,
+}
+
+#[derive(Serialize)]
+pub struct Secp256k1RecoverData {
+    address: String,
+    public_key: String,
+}
+
+fn default_personal_sign() -> bool {
+    true
+}
+
+fn decode_hex(value: &str) -> Option<Vec<u8>> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value)).ok()
+}
+
+pub fn signed_bytes(message: &str, encoding: Option<&str>, personal_sign: bool) -> Result<Vec<u8>, String> {
+    let message = decode_message(message, &encoding_option(encoding, "utf8"))?;
+
+    if !personal_sign {
+        return Ok(message);
+    }
+
+    let mut prefixed = format!("{}{}", PERSONAL_SIGN_PREFIX, message.len()).into_bytes();
+    prefixed.extend_from_slice(&message);
+    Ok(prefixed)
+}
+
+pub fn message_hash(signed_bytes: &[u8]) -> Message {
+    Message::parse(&Keccak256::digest(signed_bytes).into())
+}
+
+pub fn parse_secret_key(secret: &str) -> Result<SecretKey, String> {
+    let bytes = decode_hex(secret).ok_or("Invalid hex encoding in secret key")?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| "Invalid secret key length. Expected 32 bytes.".to_string())?;
+
+    SecretKey::parse(&bytes).map_err(|_| "Invalid secp256k1 secret key".to_string())
+}
+
+pub fn parse_signature(signature: &str) -> Result<(Signature, RecoveryId), String> {
+    let bytes = decode_hex(signature)
+        .filter(|bytes| bytes.len() == 65)
+        .ok_or("Signature must be 65 hex encoded bytes")?;
+
+    let v = match bytes[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        _ => return Err("Invalid signature recovery id".to_string()),
+    };
+
+    let signature = Signature::parse_standard_slice(&bytes[..64]).map_err(|_| "Invalid signature format".to_string())?;
+    let recovery_id = RecoveryId::parse(v).map_err(|_| "Invalid signature recovery id".to_string())?;
+
+    Ok((signature, recovery_id))
+}
+
+pub fn parse_eth_address(address: &str) -> Result<[u8; 20], String> {
+    decode_hex(address)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "Invalid Ethereum address".to_string())
+}
+
+pub fn eth_address(public_key: &PublicKey) -> [u8; 20] {
+    let mut uncompressed = [0u8; 64];
+    uncompressed.copy_from_slice(&public_key.serialize()[1..]);
+    eth_address_from_pubkey(&uncompressed)
+}
+
+pub fn checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = Keccak256::digest(lower.as_bytes());
+
+    let checksummed: String = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if nibble >= 8 { c.to_ascii_uppercase() } else { c }
+        })
+        .collect();
+
+    format!("0x{}", checksummed)
+}
+
+pub fn encode_signature(signature: &Signature, recovery_id: &RecoveryId) -> String {
+    let mut bytes = signature.serialize().to_vec();
+    bytes.push(recovery_id.serialize() + 27);
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn recover_public_key(
+    message: &str,
+    encoding: Option<&str>,
+    personal_sign: bool,
+    signature: &str,
+) -> Result<Option<PublicKey>, String> {
+    let (signature, recovery_id) = parse_signature(signature)?;
+    let bytes = signed_bytes(message, encoding, personal_sign)?;
+
+    Ok(libsecp256k1::recover(&message_hash(&bytes), &signature, &recovery_id).ok())
+}
+
+pub async fn secp256k1_sign(
+    Json(payload): Json<Secp256k1SignRequest>,
+) -> Result<Json<Secp256k1SignResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.message.is_empty() || payload.secret.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let secret_key = match parse_secret_key(&payload.secret) {
+        Ok(secret_key) => secret_key,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_secret_key",
+                error,
+            })));
+        }
+    };
+
+    let bytes = match signed_bytes(&payload.message, payload.message_encoding.as_deref(), payload.personal_sign) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_message",
+                error,
+            })));
+        }
+    };
+
+    let (signature, recovery_id) = libsecp256k1::sign(&message_hash(&bytes), &secret_key);
+    telemetry::signature_produced("secp256k1");
+    let public_key = PublicKey::from_secret_key(&secret_key);
+
+    let response = Secp256k1SignResponse {
+        success: true,
+        data: Secp256k1SignData {
+            signature: encode_signature(&signature, &recovery_id),
+            recovery_id: recovery_id.serialize(),
+            address: checksum_address(&eth_address(&public_key)),
+            public_key: format!("0x{}", hex::encode(public_key.serialize())),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn secp256k1_verify(
+    Json(payload): Json<Secp256k1VerifyRequest>,
+) -> Result<Json<Secp256k1VerifyResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.message.is_empty() || payload.signature.is_empty() || payload.address.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let address = match parse_eth_address(&payload.address) {
+        Ok(address) => address,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_address",
+                error,
+            })));
+        }
+    };
+
+    let recovered = match recover_public_key(&payload.message, payload.message_encoding.as_deref(), payload.personal_sign, &payload.signature) {
+        Ok(recovered) => recovered,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_signature",
+                error,
+            })));
+        }
+    };
+
+    let valid = recovered.as_ref().is_some_and(|recovered| eth_address(recovered) == address);
+    telemetry::signature_verified("secp256k1", valid);
+
+    let response = Secp256k1VerifyResponse {
+        success: true,
+        data: Secp256k1VerifyData {
+            valid,
+            address: checksum_address(&address),
+            recovered_address: recovered
+                .map(|recovered| checksum_address(&eth_address(&recovered)))
+                .unwrap_or_default(),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn secp256k1_recover(
+    Json(payload): Json<Secp256k1RecoverRequest>,
+) -> Result<Json<Secp256k1RecoverResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.message.is_empty() || payload.signature.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let public_key = match recover_public_key(&payload.message, payload.message_encoding.as_deref(), payload.personal_sign, &payload.signature) {
+        Ok(Some(public_key)) => public_key,
+        Ok(None) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "recovery_failed",
+                error: "Public key could not be recovered from signature".to_string(),
+            })));
+        }
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_signature",
+                error,
+            })));
+        }
+    };
+
+    let response = Secp256k1RecoverResponse {
+        success: true,
+        data: Secp256k1RecoverData {
+            address: checksum_address(&eth_address(&public_key)),
+            public_key: format!("0x{}", hex::encode(public_key.serialize())),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    const SECRET: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+    const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+    const SIGNATURE: &str = "0xb91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a0291c";
+
+    fn request<T: serde::de::DeserializeOwned>(value: Value) -> Json<T> {
+        Json(serde_json::from_value(value).unwrap())
+    }
+
+    #[tokio::test]
+    async fn matches_the_web3_personal_sign_vector() {
+        let Json(signed) = secp256k1_sign(request(json!({ "message": "Some data", "secret": SECRET }))).await.ok().unwrap();
+        assert_eq!(signed.data.address, ADDRESS);
+        assert_eq!(signed.data.signature, SIGNATURE);
+
+        let Json(recovered) = secp256k1_recover(request(json!({ "message": "Some data", "signature": SIGNATURE }))).await.ok().unwrap();
+        assert_eq!(recovered.data.address, ADDRESS);
+    }
+
+    #[tokio::test]
+    async fn recovers_what_it_signs() {
+        let secret = format!("0x{}", hex::encode([7u8; 32]));
+        let message = json!({ "message": "0xdeadbeef", "messageEncoding": "hex", "personalSign": false, "secret": secret });
+        let Json(signed) = secp256k1_sign(request(message)).await.ok().unwrap();
+
+        let Json(verified) = secp256k1_verify(request(json!({
+            "message": "0xdeadbeef",
+            "messageEncoding": "hex",
+            "personalSign": false,
+            "signature": signed.data.signature,
+            "address": signed.data.address,
+        })))
+        .await
+        .ok()
+        .unwrap();
+        assert!(verified.data.valid);
+        assert_eq!(verified.data.recovered_address, signed.data.address);
+
+        let Json(other) = secp256k1_verify(request(json!({
+            "message": "0xdeadbeef",
+            "messageEncoding": "hex",
+            "signature": signed.data.signature,
+            "address": signed.data.address,
+        })))
+        .await
+        .ok()
+        .unwrap();
+        assert!(!other.data.valid);
+    }
+
+    #[test]
+    fn rejects_bad_recovery_ids() {
+        let signature = &SIGNATURE[..SIGNATURE.len() - 2];
+
+        for v in ["00", "01", "1b", "1c"] {
+            assert!(parse_signature(&format!("{}{}", signature, v)).is_ok());
+        }
+        for v in ["02", "1a", "1d", "ff"] {
+            assert_eq!(parse_signature(&format!("{}{}", signature, v)).err().unwrap(), "Invalid signature recovery id");
+        }
+        assert!(parse_signature(signature).is_err());
+    }
+}
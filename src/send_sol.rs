@@ -1,15 +1,14 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::{
-    pubkey::Pubkey,
-    system_instruction,
-};
+use solana_system_interface::instruction as system_instruction;
 use axum::{Json, http::StatusCode};
-use std::str::FromStr;
 use base64::{Engine as _, engine::general_purpose};
+use crate::amount::{AmountInput, SOL_DECIMALS};
+use crate::pubkey::parse_pubkey;
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     success: bool,
+    code: &'static str,
     error: String,
 }
 
@@ -17,7 +16,8 @@ pub struct ErrorResponse {
 pub struct SendSolRequest {
     from: String,
     to: String,
-    lamports: i64,
+    lamports: Option<AmountInput>,
+    sol: Option<AmountInput>,
 }
 
 #[derive(Serialize)]
@@ -33,54 +33,73 @@ pub struct SendSolData {
     instruction_data: String,
 }
 
-fn is_valid_pubkey(pubkey_str: &str) -> bool {
-    match Pubkey::from_str(pubkey_str) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
-}
-
-fn is_valid_lamports(lamports: i64) -> bool {
-    lamports > 0 && lamports <= 1_000_000_000_000_000
-}
-
 pub async fn send_sol(
     Json(payload): Json<SendSolRequest>,
 ) -> Result<Json<SendSolResponse>, (StatusCode, Json<ErrorResponse>)> {
     if payload.from.is_empty() || payload.to.is_empty() {
         return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
             success: false,
+            code: "missing_fields",
             error: "Missing required fields".to_string(),
         })));
     }
 
-    if !is_valid_lamports(payload.lamports) {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Invalid lamports amount. Must be positive and not exceed 1,000,000,000,000,000".to_string(),
-        })));
-    }
+    let lamports = match (&payload.lamports, &payload.sol) {
+        (Some(lamports), None) => lamports.to_base_units(),
+        (None, Some(sol)) => sol.to_ui_base_units(SOL_DECIMALS),
+        (None, None) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_fields",
+                error: "Missing required fields".to_string(),
+            })));
+        }
+        (Some(_), Some(_)) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "conflicting_fields",
+                error: "Provide either lamports or sol, not both".to_string(),
+            })));
+        }
+    };
 
-    if !is_valid_pubkey(&payload.from) {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Invalid sender address".to_string(),
-        })));
-    }
+    let lamports = match lamports {
+        Ok(lamports) => lamports,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
-    if !is_valid_pubkey(&payload.to) {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Invalid recipient address".to_string(),
-        })));
-    }
+    let from_pubkey = match parse_pubkey(&payload.from) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_sender",
+                error: "Invalid sender address".to_string(),
+            })));
+        }
+    };
 
-    let from_pubkey = Pubkey::from_str(&payload.from).unwrap();
-    let to_pubkey = Pubkey::from_str(&payload.to).unwrap();
+    let to_pubkey = match parse_pubkey(&payload.to) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_recipient",
+                error: "Invalid recipient address".to_string(),
+            })));
+        }
+    };
 
     if from_pubkey == to_pubkey {
         return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
             success: false,
+            code: "same_address",
             error: "Cannot send SOL to the same address".to_string(),
         })));
     }
@@ -88,7 +107,7 @@ pub async fn send_sol(
     let instruction = system_instruction::transfer(
         &from_pubkey,
         &to_pubkey,
-        payload.lamports as u64,
+        lamports,
     );
 
     let instruction_data = general_purpose::STANDARD.encode(&instruction.data);
@@ -1,12 +1,16 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::pubkey::Pubkey;
-use axum::{Json, http::StatusCode};
-use std::str::FromStr;
+use serde_json::Value;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use axum::{Json, extract::State, http::StatusCode};
 use base64::{Engine as _, engine::general_purpose};
+use crate::amount::{AmountInput, check_decimals};
+use crate::state::AppState;
+use crate::pubkey::parse_pubkey;
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     success: bool,
+    code: &'static str,
     error: String,
 }
 
@@ -15,7 +19,10 @@ pub struct SendTokenRequest {
     destination: String,
     mint: String,
     owner: String,
-    amount: i64,
+    amount: Option<AmountInput>,
+    #[serde(alias = "uiAmount")]
+    ui_amount: Option<AmountInput>,
+    decimals: Option<Value>,
 }
 
 #[derive(Serialize)]
@@ -38,72 +45,152 @@ pub struct AccountInfo {
     is_signer: bool,
 }
 
-fn is_valid_pubkey(pubkey_str: &str) -> bool {
-    match Pubkey::from_str(pubkey_str) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
-}
-
 pub async fn send_token(
+    State(state): State<AppState>,
     Json(payload): Json<SendTokenRequest>,
 ) -> Result<Json<SendTokenResponse>, (StatusCode, Json<ErrorResponse>)> {
     if payload.destination.is_empty() || payload.mint.is_empty() || payload.owner.is_empty() {
         return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
             success: false,
+            code: "missing_fields",
             error: "Missing required fields".to_string(),
         })));
     }
 
-    if payload.amount <= 0 {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Amount must be greater than zero".to_string(),
-        })));
-    }
+    let decimals = match payload.decimals.as_ref().map(check_decimals).transpose() {
+        Ok(decimals) => decimals,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
-    if !is_valid_pubkey(&payload.destination) {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Invalid destination address".to_string(),
-        })));
-    }
+    let amount = match (&payload.amount, &payload.ui_amount, decimals) {
+        (Some(amount), None, _) => amount.to_base_units(),
+        (None, Some(ui_amount), Some(decimals)) => ui_amount.to_ui_base_units(decimals),
+        (None, Some(_), None) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_decimals",
+                error: "uiAmount requires decimals".to_string(),
+            })));
+        }
+        (None, None, _) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_fields",
+                error: "Missing required fields".to_string(),
+            })));
+        }
+        (Some(_), Some(_), _) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "conflicting_fields",
+                error: "Provide either amount or uiAmount, not both".to_string(),
+            })));
+        }
+    };
 
-    if !is_valid_pubkey(&payload.mint) {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Invalid mint address".to_string(),
-        })));
-    }
+    let amount = match amount {
+        Ok(amount) => amount,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
-    if !is_valid_pubkey(&payload.owner) {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Invalid owner address".to_string(),
-        })));
-    }
+    let destination_pubkey = match parse_pubkey(&payload.destination) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_destination",
+                error: "Invalid destination address".to_string(),
+            })));
+        }
+    };
 
-    let accounts = vec![
-        AccountInfo {
-            pubkey: payload.mint.clone(),
-            is_signer: false,
-        },
-        AccountInfo {
-            pubkey: payload.destination.clone(),
-            is_signer: false,
-        },
-        AccountInfo {
-            pubkey: payload.owner.clone(),
-            is_signer: true,
-        },
-    ];
+    let mint_pubkey = match parse_pubkey(&payload.mint) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_mint",
+                error: "Invalid mint address".to_string(),
+            })));
+        }
+    };
+
+    let owner_pubkey = match parse_pubkey(&payload.owner) {
+        Ok(pub_key) => pub_key,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_owner",
+                error: "Invalid owner address".to_string(),
+            })));
+        }
+    };
+
+    let token_program = state.config.token_program;
+    let source_ata = get_associated_token_address_with_program_id(&owner_pubkey, &mint_pubkey, &token_program);
+    let destination_ata = get_associated_token_address_with_program_id(&destination_pubkey, &mint_pubkey, &token_program);
+
+    let instruction = match decimals {
+        Some(decimals) => spl_token_2022::instruction::transfer_checked(
+            &token_program,
+            &source_ata,
+            &mint_pubkey,
+            &destination_ata,
+            &owner_pubkey,
+            &[],
+            amount,
+            decimals,
+        ),
+        None if token_program == spl_token::id() => {
+            spl_token::instruction::transfer(&token_program, &source_ata, &destination_ata, &owner_pubkey, &[], amount)
+        }
+        None => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "missing_decimals",
+                error: "Decimals are required for Token-2022 transfers".to_string(),
+            })));
+        }
+    };
+
+    let instruction = match instruction {
+        Ok(ix) => ix,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "instruction_failed",
+                error: "Failed to create token transfer instruction".to_string(),
+            })));
+        }
+    };
+
+    let accounts: Vec<AccountInfo> = instruction
+        .accounts
+        .iter()
+        .map(|acc| AccountInfo {
+            pubkey: acc.pubkey.to_string(),
+            is_signer: acc.is_signer,
+        })
+        .collect();
 
     let response = SendTokenResponse {
         success: true,
         data: SendTokenData {
-            program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".to_string(),
+            program_id: instruction.program_id.to_string(),
             accounts,
-            instruction_data: general_purpose::STANDARD.encode(&[1, 2, 3, 4]),
+            instruction_data: general_purpose::STANDARD.encode(&instruction.data),
         },
     };
 
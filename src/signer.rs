@@ -0,0 +1,422 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+};
+use axum::http::StatusCode;
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose};
+use crate::config::SignerBackendKind;
+use crate::keystore::{Keystore, KeystoreError};
+use crate::pubkey::parse_pubkey;
+use crate::state::AppState;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const REMOTE_SIGNER_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug)]
+pub enum SignerError {
+    NotConfigured,
+    Keystore(KeystoreError),
+    Remote(String),
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerError::NotConfigured => write!(f, "No signer backend is configured for key ids"),
+            SignerError::Keystore(e) => write!(f, "{}", e),
+            SignerError::Remote(reason) => write!(f, "Remote signer error: {}", reason),
+        }
+    }
+}
+
+impl SignerError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            SignerError::NotConfigured => StatusCode::SERVICE_UNAVAILABLE,
+            SignerError::Keystore(e) => e.status(),
+            SignerError::Remote(_) => StatusCode::BAD_GATEWAY,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            SignerError::NotConfigured => "signer_not_configured",
+            SignerError::Keystore(e) => e.code(),
+            SignerError::Remote(_) => "remote_signer",
+        }
+    }
+}
+
+#[async_trait]
+pub trait SignerBackend: Send + Sync {
+    async fn pubkey(&self) -> Result<Pubkey, SignerError>;
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, SignerError>;
+}
+
+pub struct InlineSigner {
+    keypair: Keypair,
+}
+
+impl InlineSigner {
+    pub fn new(keypair: Keypair) -> Self {
+        InlineSigner { keypair }
+    }
+}
+
+#[async_trait]
+impl SignerBackend for InlineSigner {
+    async fn pubkey(&self) -> Result<Pubkey, SignerError> {
+        Ok(self.keypair.pubkey())
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        Ok(self.keypair.sign_message(message))
+    }
+}
+
+pub struct KeystoreSigner {
+    keystore: Arc<Keystore>,
+    key_id: String,
+}
+
+#[async_trait]
+impl SignerBackend for KeystoreSigner {
+    async fn pubkey(&self) -> Result<Pubkey, SignerError> {
+        self.keystore.pubkey(&self.key_id).map_err(SignerError::Keystore)
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let keypair = self.keystore.keypair(&self.key_id).map_err(SignerError::Keystore)?;
+
+        Ok(keypair.sign_message(message))
+    }
+}
+
+pub struct RemoteSigner {
+    url: String,
+    token: Option<String>,
+    client: reqwest::Client,
+    pubkeys: Mutex<HashMap<String, Pubkey>>,
+}
+
+impl RemoteSigner {
+    pub fn new(url: &str, token: Option<String>) -> Option<RemoteSigner> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(REMOTE_SIGNER_TIMEOUT_SECS))
+            .build()
+            .ok()?;
+
+        Some(RemoteSigner {
+            url: url.trim_end_matches('/').to_string(),
+            token,
+            client,
+            pubkeys: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+pub struct RemoteKey {
+    signer: Arc<RemoteSigner>,
+    key_id: String,
+}
+
+#[derive(Serialize)]
+struct RemoteRequest<'a> {
+    key_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RemoteResponse {
+    success: bool,
+    data: Option<RemoteData>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RemoteData {
+    pubkey: String,
+    signature: Option<String>,
+}
+
+impl RemoteKey {
+    async fn call(&self, path: &str, message: Option<&[u8]>) -> Result<RemoteData, SignerError> {
+        let mut request = self
+            .signer
+            .client
+            .post(format!("{}{}", self.signer.url, path))
+            .json(&RemoteRequest {
+                key_id: &self.key_id,
+                message: message.map(|message| general_purpose::STANDARD.encode(message)),
+            });
+
+        if let Some(token) = &self.signer.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| SignerError::Remote(e.to_string()))?
+            .json::<RemoteResponse>()
+            .await
+            .map_err(|e| SignerError::Remote(e.to_string()))?;
+
+        match (response.success, response.data) {
+            (true, Some(data)) => Ok(data),
+            _ => Err(SignerError::Remote(response.error.unwrap_or_else(|| "request failed".to_string()))),
+        }
+    }
+
+    fn cached_pubkey(&self) -> Option<Pubkey> {
+        self.signer.pubkeys.lock().unwrap().get(&self.key_id).copied()
+    }
+
+    fn remember_pubkey(&self, pubkey: &str) -> Result<Pubkey, SignerError> {
+        let pubkey = parse_pubkey(pubkey).map_err(|e| SignerError::Remote(e.to_string()))?;
+
+        let mut pubkeys = self.signer.pubkeys.lock().unwrap();
+        if *pubkeys.entry(self.key_id.clone()).or_insert(pubkey) != pubkey {
+            return Err(SignerError::Remote("signer returned a different public key".to_string()));
+        }
+
+        Ok(pubkey)
+    }
+}
+
+#[async_trait]
+impl SignerBackend for RemoteKey {
+    async fn pubkey(&self) -> Result<Pubkey, SignerError> {
+        if let Some(pubkey) = self.cached_pubkey() {
+            return Ok(pubkey);
+        }
+
+        let data = self.call("/signer/pubkey", None).await?;
+        self.remember_pubkey(&data.pubkey)
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let data = self.call("/signer/sign", Some(message)).await?;
+        let pubkey = self.remember_pubkey(&data.pubkey)?;
+
+        let signature = data
+            .signature
+            .as_deref()
+            .and_then(|signature| Signature::from_str(signature).ok())
+            .ok_or_else(|| SignerError::Remote("invalid signature in response".to_string()))?;
+
+        if !signature.verify(pubkey.as_ref(), message) {
+            return Err(SignerError::Remote("signature does not verify".to_string()));
+        }
+
+        Ok(signature)
+    }
+}
+
+pub fn key_signer(state: &AppState, key_id: &str) -> Result<Box<dyn SignerBackend>, SignerError> {
+    match state.config.signer_backend {
+        SignerBackendKind::Keystore => match &state.keystore {
+            Some(keystore) => Ok(Box::new(KeystoreSigner {
+                keystore: keystore.clone(),
+                key_id: key_id.to_string(),
+            })),
+            None => Err(SignerError::Keystore(KeystoreError::NotConfigured)),
+        },
+        SignerBackendKind::Remote => match &state.remote_signer {
+            Some(signer) => Ok(Box::new(RemoteKey {
+                signer: signer.clone(),
+                key_id: key_id.to_string(),
+            })),
+            None => Err(SignerError::NotConfigured),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use axum::{Json, Router, extract::State, http::HeaderMap, routing::post};
+    use serde_json::{Value, json};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const KEY_ID: &str = "hot-wallet";
+    const TOKEN: &str = "signer-token";
+
+    #[derive(Clone, Copy)]
+    enum Behavior {
+        Honest,
+        WrongSignature,
+        RotatingKey,
+    }
+
+    struct MockSigner {
+        keypair: Keypair,
+        behavior: Behavior,
+        calls: AtomicUsize,
+    }
+
+    #[derive(Deserialize)]
+    struct MockRequest {
+        key_id: String,
+        message: Option<String>,
+    }
+
+    async fn mock_handler(
+        mock: Arc<MockSigner>,
+        headers: HeaderMap,
+        request: MockRequest,
+    ) -> Json<Value> {
+        let calls = mock.calls.fetch_add(1, Ordering::SeqCst);
+
+        let authorized = headers
+            .get("authorization")
+            .is_some_and(|value| value.as_bytes() == format!("Bearer {}", TOKEN).as_bytes());
+        if !authorized {
+            return Json(json!({ "success": false, "error": "unauthorized" }));
+        }
+
+        if request.key_id != KEY_ID {
+            return Json(json!({ "success": false, "error": "unknown key" }));
+        }
+
+        let keypair = match mock.behavior {
+            Behavior::RotatingKey if calls > 0 => Keypair::new(),
+            _ => mock.keypair.insecure_clone(),
+        };
+
+        let signature = request.message.map(|message| {
+            let message = general_purpose::STANDARD.decode(message).unwrap();
+            match mock.behavior {
+                Behavior::WrongSignature => keypair.sign_message(b"something else"),
+                _ => keypair.sign_message(&message),
+            }
+            .to_string()
+        });
+
+        Json(json!({
+            "success": true,
+            "data": { "pubkey": keypair.pubkey().to_string(), "signature": signature },
+        }))
+    }
+
+    async fn mock_pubkey(
+        State(mock): State<Arc<MockSigner>>,
+        headers: HeaderMap,
+        Json(request): Json<MockRequest>,
+    ) -> Json<Value> {
+        mock_handler(mock, headers, MockRequest { message: None, ..request }).await
+    }
+
+    async fn mock_sign(
+        State(mock): State<Arc<MockSigner>>,
+        headers: HeaderMap,
+        Json(request): Json<MockRequest>,
+    ) -> Json<Value> {
+        mock_handler(mock, headers, request).await
+    }
+
+    async fn spawn_mock(behavior: Behavior) -> (Arc<MockSigner>, String) {
+        let mock = Arc::new(MockSigner {
+            keypair: Keypair::new(),
+            behavior,
+            calls: AtomicUsize::new(0),
+        });
+
+        let app = Router::new()
+            .route("/signer/pubkey", post(mock_pubkey))
+            .route("/signer/sign", post(mock_sign))
+            .with_state(mock.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (mock, url)
+    }
+
+    fn remote_key(signer: &Arc<RemoteSigner>, key_id: &str) -> RemoteKey {
+        RemoteKey {
+            signer: signer.clone(),
+            key_id: key_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn signs_with_the_remote_key() {
+        let (mock, url) = spawn_mock(Behavior::Honest).await;
+        let signer = Arc::new(RemoteSigner::new(&url, Some(TOKEN.to_string())).unwrap());
+
+        let key = remote_key(&signer, KEY_ID);
+        let signature = key.sign(b"hello").await.unwrap();
+
+        assert!(signature.verify(mock.keypair.pubkey().as_ref(), b"hello"));
+        assert_eq!(key.pubkey().await.unwrap(), mock.keypair.pubkey());
+    }
+
+    #[tokio::test]
+    async fn caches_the_pubkey_across_requests() {
+        let (mock, url) = spawn_mock(Behavior::Honest).await;
+        let signer = Arc::new(RemoteSigner::new(&url, Some(TOKEN.to_string())).unwrap());
+
+        for _ in 0..3 {
+            assert_eq!(remote_key(&signer, KEY_ID).pubkey().await.unwrap(), mock.keypair.pubkey());
+        }
+
+        assert_eq!(mock.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_signature_that_does_not_verify() {
+        let (_mock, url) = spawn_mock(Behavior::WrongSignature).await;
+        let signer = Arc::new(RemoteSigner::new(&url, Some(TOKEN.to_string())).unwrap());
+
+        let error = remote_key(&signer, KEY_ID).sign(b"hello").await.unwrap_err();
+
+        assert_eq!(error.to_string(), "Remote signer error: signature does not verify");
+        assert_eq!(error.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn rejects_a_changed_pubkey() {
+        let (_mock, url) = spawn_mock(Behavior::RotatingKey).await;
+        let signer = Arc::new(RemoteSigner::new(&url, Some(TOKEN.to_string())).unwrap());
+
+        remote_key(&signer, KEY_ID).sign(b"hello").await.unwrap();
+        let error = remote_key(&signer, KEY_ID).sign(b"hello").await.unwrap_err();
+
+        assert_eq!(error.to_string(), "Remote signer error: signer returned a different public key");
+    }
+
+    #[tokio::test]
+    async fn uses_only_the_configured_backend() {
+        let (_mock, url) = spawn_mock(Behavior::Honest).await;
+        let mut state = AppState::for_tests(Config::from_toml(""));
+        state.remote_signer = Some(Arc::new(RemoteSigner::new(&url, Some(TOKEN.to_string())).unwrap()));
+
+        let error = key_signer(&state, KEY_ID).err().unwrap();
+        assert_eq!(error.code(), "keystore_not_configured");
+    }
+
+    #[tokio::test]
+    async fn reports_remote_errors() {
+        let (_mock, url) = spawn_mock(Behavior::Honest).await;
+
+        let unauthenticated = Arc::new(RemoteSigner::new(&url, None).unwrap());
+        let error = remote_key(&unauthenticated, KEY_ID).pubkey().await.unwrap_err();
+        assert_eq!(error.to_string(), "Remote signer error: unauthorized");
+
+        let signer = Arc::new(RemoteSigner::new(&url, Some(TOKEN.to_string())).unwrap());
+        let error = remote_key(&signer, "missing").sign(b"hello").await.unwrap_err();
+        assert_eq!(error.to_string(), "Remote signer error: unknown key");
+    }
+}
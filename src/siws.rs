@@ -0,0 +1,656 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use axum::{Json, extract::{ConnectInfo, State}, http::{HeaderMap, StatusCode, header}};
+use base64::{Engine as _, engine::general_purpose};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use crate::pubkey::parse_pubkey;
+use crate::rate_limit::client_prefix;
+use crate::state::AppState;
+use crate::telemetry;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+
+const DEFAULT_EXPIRES_IN_SECS: i64 = 300;
+const MAX_EXPIRES_IN_SECS: i64 = 600;
+const MAX_PENDING_CHALLENGES: usize = 10_000;
+const MAX_CHALLENGES_PER_CLIENT: usize = 5;
+const SESSION_TTL_SECS: i64 = 86_400;
+const MESSAGE_VERSION: &str = "1";
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+struct PendingChallenge {
+    client: IpAddr,
+    message: String,
+    issued_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+struct Session {
+    address: Pubkey,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct SiwsStore {
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SiwsStore {
+    fn issue(&self, nonce: String, challenge: PendingChallenge) {
+        let now = Utc::now();
+        let mut challenges = self.challenges.lock().unwrap();
+        challenges.retain(|_, pending| pending.expires_at > now);
+
+        while challenges.values().filter(|pending| pending.client == challenge.client).count() >= MAX_CHALLENGES_PER_CLIENT {
+            evict_oldest(&mut challenges, |pending| pending.client == challenge.client);
+        }
+
+        if challenges.len() >= MAX_PENDING_CHALLENGES {
+            evict_oldest(&mut challenges, |_| true);
+        }
+
+        challenges.insert(nonce, challenge);
+    }
+
+    fn redeem(&self, nonce: &str, message: &str) -> Result<(), (&'static str, &'static str)> {
+        let mut challenges = self.challenges.lock().unwrap();
+
+        match challenges.get(nonce) {
+            None => return Err(("unknown_nonce", "Unknown or already used nonce")),
+            Some(pending) if pending.message != message => {
+                return Err(("challenge_mismatch", "Message does not match the issued challenge"));
+            }
+            Some(pending) if Utc::now() >= pending.expires_at => return Err(("challenge_expired", "Challenge has expired")),
+            Some(_) => {}
+        }
+
+        challenges.remove(nonce);
+        Ok(())
+    }
+
+    fn start_session(&self, address: Pubkey) -> (String, DateTime<Utc>) {
+        let now = Utc::now();
+        let token = bs58::encode(rand::random::<[u8; 32]>()).into_string();
+        let expires_at = now + Duration::seconds(SESSION_TTL_SECS);
+
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(token.clone(), Session { address, expires_at });
+
+        (token, expires_at)
+    }
+
+    fn session(&self, token: &str) -> Option<(Pubkey, DateTime<Utc>)> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(token)
+            .filter(|session| session.expires_at > Utc::now())
+            .map(|session| (session.address, session.expires_at))
+    }
+}
+
+fn evict_oldest(challenges: &mut HashMap<String, PendingChallenge>, filter: impl Fn(&PendingChallenge) -> bool) {
+    let oldest = challenges
+        .iter()
+        .filter(|(_, pending)| filter(pending))
+        .min_by_key(|(_, pending)| pending.issued_at)
+        .map(|(nonce, _)| nonce.clone());
+
+    if let Some(nonce) = oldest {
+        challenges.remove(&nonce);
+    }
+}
+
+fn chain_id(cluster: &str) -> &str {
+    match cluster {
+        "mainnet-beta" => "mainnet",
+        cluster => cluster,
+    }
+}
+
+fn is_allowed_domain(state: &AppState, domain: &str) -> bool {
+    state.config.siws_domains.iter().any(|allowed| allowed.eq_ignore_ascii_case(domain))
+}
+
+struct SiwsMessage {
+    domain: String,
+    address: Pubkey,
+    statement: Option<String>,
+    chain_id: String,
+    nonce: String,
+    issued_at: DateTime<Utc>,
+    expiration_time: DateTime<Utc>,
+}
+
+fn timestamp(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+impl SiwsMessage {
+    fn to_text(&self) -> String {
+        let mut text = format!("{}{}\n{}\n", self.domain, HEADER_SUFFIX, self.address);
+        if let Some(statement) = &self.statement {
+            text.push_str(&format!("\n{}\n", statement));
+        }
+        text.push_str(&format!(
+            "\nVersion: {}\nChain ID: {}\nNonce: {}\nIssued At: {}\nExpiration Time: {}",
+            MESSAGE_VERSION,
+            self.chain_id,
+            self.nonce,
+            timestamp(&self.issued_at),
+            timestamp(&self.expiration_time),
+        ));
+        text
+    }
+
+    fn parse(text: &str) -> Result<SiwsMessage, String> {
+        let mut lines = text.split('\n');
+
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or("Missing sign-in header")?
+            .to_string();
+
+        let address = lines
+            .next()
+            .and_then(|line| parse_pubkey(line).ok())
+            .ok_or("Invalid address in message")?;
+
+        if lines.next() != Some("") {
+            return Err("Malformed message".to_string());
+        }
+
+        let mut line = lines.next().ok_or("Malformed message")?;
+        let statement = if line.starts_with("Version: ") {
+            None
+        } else {
+            let statement = line.to_string();
+            if lines.next() != Some("") {
+                return Err("Malformed message".to_string());
+            }
+            line = lines.next().ok_or("Malformed message")?;
+            Some(statement)
+        };
+
+        let field = |name: &str, line: Option<&str>| -> Result<String, String> {
+            line.and_then(|line| line.strip_prefix(name))
+                .and_then(|line| line.strip_prefix(": "))
+                .map(|value| value.to_string())
+                .ok_or_else(|| format!("Missing {} in message", name))
+        };
+
+        let version = field("Version", Some(line))?;
+        if version != MESSAGE_VERSION {
+            return Err(format!("Unsupported message version: {}", version));
+        }
+
+        let chain_id = field("Chain ID", lines.next())?;
+        let nonce = field("Nonce", lines.next())?;
+        let issued_at = field("Issued At", lines.next())?;
+        let expiration_time = field("Expiration Time", lines.next())?;
+
+        if lines.next().is_some() {
+            return Err("Unexpected trailing content in message".to_string());
+        }
+
+        let parse_time = |value: &str, name: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|time| time.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid {} in message", name))
+        };
+
+        Ok(SiwsMessage {
+            domain,
+            address,
+            statement,
+            chain_id,
+            nonce,
+            issued_at: parse_time(&issued_at, "Issued At")?,
+            expiration_time: parse_time(&expiration_time, "Expiration Time")?,
+        })
+    }
+}
+
+fn is_single_line(value: &str) -> bool {
+    !value.contains('\n') && !value.contains('\r')
+}
+
+#[derive(Deserialize)]
+pub struct ChallengeRequest {
+    domain: String,
+    address: String,
+    statement: Option<String>,
+    #[serde(alias = "chainId")]
+    chain_id: Option<String>,
+    #[serde(alias = "expiresIn")]
+    expires_in: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ChallengeResponse {
+    success: bool,
+    data: ChallengeData,
+}
+
+#[derive(Serialize)]
+pub struct ChallengeData {
+    message: String,
+    domain: String,
+    address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statement: Option<String>,
+    chain_id: String,
+    nonce: String,
+    issued_at: String,
+    expiration_time: String,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyRequest {
+    message: String,
+    signature: String,
+}
+
+#[derive(Serialize)]
+pub struct SessionResponse {
+    success: bool,
+    data: SessionData,
+}
+
+#[derive(Serialize)]
+pub struct SessionData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+    address: String,
+    expires_at: String,
+}
+
+fn unauthorized(code: &'static str, error: &str) -> (StatusCode, Json<ErrorResponse>) {
+    (StatusCode::UNAUTHORIZED, Json(ErrorResponse {
+        success: false,
+        code,
+        error: error.to_string(),
+    }))
+}
+
+pub async fn siws_challenge(
+    State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<ChallengeRequest>,
+) -> Result<Json<ChallengeResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.domain.is_empty() || payload.address.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let address = match parse_pubkey(&payload.address) {
+        Ok(address) => address,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_pubkey",
+                error: "Invalid public key format".to_string(),
+            })));
+        }
+    };
+
+    if !is_allowed_domain(&state, &payload.domain) {
+        return Err((StatusCode::FORBIDDEN, Json(ErrorResponse {
+            success: false,
+            code: "domain_not_allowed",
+            error: "Sign-in is not enabled for this domain".to_string(),
+        })));
+    }
+
+    let chain_id = payload.chain_id.unwrap_or_else(|| chain_id(&state.config.cluster).to_string());
+    let statement = payload.statement.filter(|statement| !statement.is_empty());
+
+    if payload.domain.contains(char::is_whitespace)
+        || chain_id.is_empty()
+        || chain_id.contains(char::is_whitespace)
+        || !statement.as_deref().is_none_or(is_single_line)
+        || statement.as_deref().is_some_and(|statement| statement.starts_with("Version: "))
+    {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "invalid_challenge_fields",
+            error: "Domain, statement and chain id must each be a single line".to_string(),
+        })));
+    }
+
+    let expires_in = payload.expires_in.unwrap_or(DEFAULT_EXPIRES_IN_SECS);
+    if expires_in <= 0 || expires_in > MAX_EXPIRES_IN_SECS {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "invalid_expiration",
+            error: format!("Expiration must be between 1 and {} seconds", MAX_EXPIRES_IN_SECS),
+        })));
+    }
+
+    let issued_at = Utc::now();
+    let message = SiwsMessage {
+        domain: payload.domain,
+        address,
+        statement,
+        chain_id,
+        nonce: uuid::Uuid::new_v4().simple().to_string(),
+        issued_at,
+        expiration_time: issued_at + Duration::seconds(expires_in),
+    };
+    let text = message.to_text();
+
+    let client = client_prefix(state.rate_limiter.client_ip(peer.ip(), &headers));
+    state.siws.issue(message.nonce.clone(), PendingChallenge {
+        client,
+        message: text.clone(),
+        issued_at,
+        expires_at: message.expiration_time,
+    });
+
+    let response = ChallengeResponse {
+        success: true,
+        data: ChallengeData {
+            message: text,
+            domain: message.domain,
+            address: message.address.to_string(),
+            statement: message.statement,
+            chain_id: message.chain_id,
+            nonce: message.nonce,
+            issued_at: timestamp(&message.issued_at),
+            expiration_time: timestamp(&message.expiration_time),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn siws_verify(
+    State(state): State<AppState>,
+    Json(payload): Json<VerifyRequest>,
+) -> Result<Json<SessionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.message.is_empty() || payload.signature.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let message = match SiwsMessage::parse(&payload.message) {
+        Ok(message) => message,
+        Err(error) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_siws_message",
+                error,
+            })));
+        }
+    };
+
+    if !is_allowed_domain(&state, &message.domain) {
+        return Err(unauthorized("domain_not_allowed", "Sign-in is not enabled for this domain"));
+    }
+
+    let signature = match general_purpose::STANDARD
+        .decode(&payload.signature)
+        .ok()
+        .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+    {
+        Some(signature) => signature,
+        None => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_signature",
+                error: "Invalid signature format".to_string(),
+            })));
+        }
+    };
+
+    let valid = signature.verify(message.address.as_ref(), payload.message.as_bytes());
+    telemetry::signature_verified("ed25519", valid);
+    if !valid {
+        return Err(unauthorized("invalid_signature", "Invalid signature"));
+    }
+
+    if Utc::now() >= message.expiration_time {
+        return Err(unauthorized("challenge_expired", "Challenge has expired"));
+    }
+
+    if let Err((code, error)) = state.siws.redeem(&message.nonce, &payload.message) {
+        return Err(unauthorized(code, error));
+    }
+
+    let (token, expires_at) = state.siws.start_session(message.address);
+
+    let response = SessionResponse {
+        success: true,
+        data: SessionData {
+            token: Some(token),
+            address: message.address.to_string(),
+            expires_at: timestamp(&expires_at),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn siws_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Json<SessionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let token = match headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        Some(token) => token.trim(),
+        None => return Err(unauthorized("missing_token", "Missing bearer token")),
+    };
+
+    let (address, expires_at) = match state.siws.session(token) {
+        Some(session) => session,
+        None => return Err(unauthorized("invalid_session", "Invalid or expired session")),
+    };
+
+    let response = SessionResponse {
+        success: true,
+        data: SessionData {
+            token: None,
+            address: address.to_string(),
+            expires_at: timestamp(&expires_at),
+        },
+    };
+
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use serde_json::json;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    const DOMAIN: &str = "app.example.com";
+
+    fn test_state() -> AppState {
+        AppState::for_tests(Config::from_toml(&format!("siws_domains = [\"{}\"]\ncluster = \"devnet\"", DOMAIN)))
+    }
+
+    fn peer(ip: &str) -> ConnectInfo<SocketAddr> {
+        ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 40000))
+    }
+
+    async fn challenge(state: &AppState, domain: &str, address: &Pubkey) -> Result<ChallengeData, (StatusCode, &'static str)> {
+        let request = serde_json::from_value(json!({ "domain": domain, "address": address.to_string() })).unwrap();
+        siws_challenge(State(state.clone()), peer("203.0.113.7"), HeaderMap::new(), Json(request))
+            .await
+            .map(|Json(response)| response.data)
+            .map_err(|(status, Json(error))| (status, error.code))
+    }
+
+    async fn verify(state: &AppState, keypair: &Keypair, message: &str) -> Result<SessionData, (StatusCode, &'static str)> {
+        let signature = keypair.sign_message(message.as_bytes());
+        let request = VerifyRequest {
+            message: message.to_string(),
+            signature: general_purpose::STANDARD.encode(signature.as_ref()),
+        };
+        siws_verify(State(state.clone()), Json(request))
+            .await
+            .map(|Json(response)| response.data)
+            .map_err(|(status, Json(error))| (status, error.code))
+    }
+
+    fn pending(client: &str, issued_at: DateTime<Utc>) -> PendingChallenge {
+        PendingChallenge {
+            client: client.parse().unwrap(),
+            message: String::new(),
+            issued_at,
+            expires_at: issued_at + Duration::seconds(DEFAULT_EXPIRES_IN_SECS),
+        }
+    }
+
+    #[test]
+    fn message_text_round_trips() {
+        let issued_at = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z").unwrap().with_timezone(&Utc);
+
+        for statement in [None, Some("Sign in to the dashboard".to_string())] {
+            let message = SiwsMessage {
+                domain: DOMAIN.to_string(),
+                address: Pubkey::new_unique(),
+                statement,
+                chain_id: "devnet".to_string(),
+                nonce: "3f1c0a9b".to_string(),
+                issued_at,
+                expiration_time: issued_at + Duration::seconds(300),
+            };
+
+            let text = message.to_text();
+            let parsed = SiwsMessage::parse(&text).unwrap();
+
+            assert_eq!(parsed.domain, message.domain);
+            assert_eq!(parsed.address, message.address);
+            assert_eq!(parsed.statement, message.statement);
+            assert_eq!(parsed.chain_id, message.chain_id);
+            assert_eq!(parsed.nonce, message.nonce);
+            assert_eq!(parsed.issued_at, message.issued_at);
+            assert_eq!(parsed.expiration_time, message.expiration_time);
+            assert_eq!(parsed.to_text(), text);
+        }
+
+        assert!(SiwsMessage::parse("example.com wants you to sign in").is_err());
+    }
+
+    #[tokio::test]
+    async fn signs_in_for_an_allowed_domain() {
+        let state = test_state();
+        let keypair = Keypair::new();
+
+        let data = challenge(&state, DOMAIN, &keypair.pubkey()).await.unwrap();
+        assert_eq!(data.chain_id, "devnet");
+
+        let session = verify(&state, &keypair, &data.message).await.unwrap();
+        assert_eq!(session.address, keypair.pubkey().to_string());
+        assert_eq!(verify(&state, &keypair, &data.message).await.err().unwrap().1, "unknown_nonce");
+    }
+
+    #[tokio::test]
+    async fn rejects_challenges_for_other_domains() {
+        let state = test_state();
+        let address = Pubkey::new_unique();
+
+        for domain in ["evil.example.com", "app.example.com.evil.com", "example.com"] {
+            assert_eq!(challenge(&state, domain, &address).await.err().unwrap(), (StatusCode::FORBIDDEN, "domain_not_allowed"));
+        }
+        assert!(challenge(&state, "APP.example.com", &address).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_verification_for_other_domains() {
+        let state = test_state();
+        let keypair = Keypair::new();
+
+        let data = challenge(&state, DOMAIN, &keypair.pubkey()).await.unwrap();
+        let forged = data.message.replacen(DOMAIN, "evil.example.com", 1);
+
+        assert_eq!(verify(&state, &keypair, &forged).await.err().unwrap(), (StatusCode::UNAUTHORIZED, "domain_not_allowed"));
+    }
+
+    #[tokio::test]
+    async fn rejects_expired_challenges() {
+        let state = test_state();
+        let keypair = Keypair::new();
+        let issued_at = Utc::now() - Duration::seconds(120);
+
+        let message = SiwsMessage {
+            domain: DOMAIN.to_string(),
+            address: keypair.pubkey(),
+            statement: None,
+            chain_id: "devnet".to_string(),
+            nonce: "expired".to_string(),
+            issued_at,
+            expiration_time: issued_at + Duration::seconds(60),
+        };
+        let text = message.to_text();
+
+        assert_eq!(verify(&state, &keypair, &text).await.err().unwrap(), (StatusCode::UNAUTHORIZED, "challenge_expired"));
+
+        let store = SiwsStore::default();
+        store.challenges.lock().unwrap().insert("expired".to_string(), PendingChallenge {
+            message: text.clone(),
+            expires_at: message.expiration_time,
+            ..pending("203.0.113.7", issued_at)
+        });
+        assert_eq!(store.redeem("expired", &text).unwrap_err().0, "challenge_expired");
+    }
+
+    #[test]
+    fn evicts_the_oldest_challenge_per_client() {
+        let store = SiwsStore::default();
+        let start = Utc::now();
+
+        for i in 0..=MAX_CHALLENGES_PER_CLIENT {
+            store.issue(format!("a{}", i), pending("203.0.113.7", start + Duration::seconds(i as i64)));
+        }
+        store.issue("b".to_string(), pending("198.51.100.1", start));
+
+        let challenges = store.challenges.lock().unwrap();
+        assert!(!challenges.contains_key("a0"));
+        assert!((1..=MAX_CHALLENGES_PER_CLIENT).all(|i| challenges.contains_key(&format!("a{}", i))));
+        assert!(challenges.contains_key("b"));
+    }
+
+    #[test]
+    fn evicts_the_oldest_challenge_when_full() {
+        let store = SiwsStore::default();
+        let start = Utc::now();
+
+        for i in 0..MAX_PENDING_CHALLENGES {
+            let client = format!("10.{}.{}.{}", i >> 16, (i >> 8) & 0xff, i & 0xff);
+            store.issue(format!("n{}", i), pending(&client, start + Duration::milliseconds(i as i64)));
+        }
+        store.issue("latest".to_string(), pending("198.51.100.1", start + Duration::seconds(60)));
+
+        let challenges = store.challenges.lock().unwrap();
+        assert_eq!(challenges.len(), MAX_PENDING_CHALLENGES);
+        assert!(!challenges.contains_key("n0"));
+        assert!(challenges.contains_key("n1"));
+        assert!(challenges.contains_key("latest"));
+    }
+}
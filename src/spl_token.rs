@@ -1,13 +1,16 @@
 use serde::{Deserialize, Serialize};
-use solana_sdk::pubkey::Pubkey;
-use spl_token::instruction::initialize_mint;
-use axum::{Json, http::StatusCode};
+use serde_json::Value;
+use spl_token_2022::instruction::initialize_mint;
+use axum::{Json, extract::State, http::StatusCode};
+use crate::pubkey::parse_pubkey;
 use base64::{Engine as _, engine::general_purpose};
-use std::str::FromStr;
+use crate::amount::check_decimals;
+use crate::state::AppState;
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     success: bool,
+    code: &'static str,
     error: String,
 }
 
@@ -16,7 +19,8 @@ pub struct InitializeMint {
     #[serde(alias = "mintAuthority")]
     mint_authority: String,
     mint: String,
-    decimals: i32,
+    #[serde(default)]
+    decimals: Value,
 }
 
 #[derive(Serialize)]
@@ -40,46 +44,54 @@ pub struct AccountData {
 }
 
 pub async fn spl_token_initialize_mint_instruction(
+    State(state): State<AppState>,
     Json(payload): Json<InitializeMint>,
 ) -> Result<Json<MintResponse>, (StatusCode, Json<ErrorResponse>)> {
-    if payload.decimals < 0 || payload.decimals > 9 {
-        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
-            success: false,
-            error: "Decimals must be between 0 and 9".to_string(),
-        })));
-    }
+    let decimals = match check_decimals(&payload.decimals) {
+        Ok(decimals) => decimals,
+        Err(e) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
 
-    let mint_authority = match Pubkey::from_str(&payload.mint_authority) {
+    let mint_authority = match parse_pubkey(&payload.mint_authority) {
         Ok(pub_key) => pub_key,
         Err(_) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
+                code: "invalid_authority",
                 error: "Invalid mint authority public key".to_string(),
             })));
         }
     };
 
-    let mint = match Pubkey::from_str(&payload.mint) {
+    let mint = match parse_pubkey(&payload.mint) {
         Ok(pub_key) => pub_key,
         Err(_) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
+                code: "invalid_mint",
                 error: "Invalid mint public key".to_string(),
             })));
         }
     };
 
     let instruction = match initialize_mint(
-        &spl_token::id(),
+        &state.config.token_program,
         &mint,
         &mint_authority,
         None,
-        payload.decimals as u8,
+        decimals,
     ) {
         Ok(ix) => ix,
         Err(_) => {
             return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
                 success: false,
+                code: "instruction_failed",
                 error: "Failed to create mint instruction".to_string(),
             })));
         }
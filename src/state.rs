@@ -0,0 +1,47 @@
+use metrics_exporter_prometheus::PrometheusHandle;
+use crate::auth::ApiKeys;
+use crate::config::Config;
+use crate::health::RpcProbe;
+use crate::keystore::Keystore;
+use crate::rate_limit::RateLimiter;
+use crate::signer::RemoteSigner;
+use crate::siws::SiwsStore;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use tokio::sync::Semaphore;
+
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<Config>,
+    pub api_keys: Arc<ApiKeys>,
+    pub rate_limiter: Arc<RateLimiter>,
+    pub keystore: Option<Arc<Keystore>>,
+    pub remote_signer: Option<Arc<RemoteSigner>>,
+    pub rpc_probe: Option<Arc<RpcProbe>>,
+    pub siws: Arc<SiwsStore>,
+    pub grinds: Arc<Semaphore>,
+    pub batches: Arc<Semaphore>,
+    pub ready: Arc<AtomicBool>,
+    pub metrics: PrometheusHandle,
+}
+
+#[cfg(test)]
+impl AppState {
+    pub fn for_tests(config: Config) -> AppState {
+        let config = Arc::new(config);
+
+        AppState {
+            api_keys: Arc::new(ApiKeys::new(&config.api_keys)),
+            rate_limiter: RateLimiter::new(&config),
+            keystore: None,
+            remote_signer: None,
+            rpc_probe: None,
+            siws: Arc::new(SiwsStore::default()),
+            grinds: Arc::new(Semaphore::new(config.max_concurrent_grinds)),
+            batches: Arc::new(Semaphore::new(config.max_concurrent_batches)),
+            ready: Arc::new(AtomicBool::new(true)),
+            metrics: metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder().handle(),
+            config,
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, Response, StatusCode, header},
+    middleware::Next,
+};
+use metrics::{counter, gauge, histogram};
+use metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder, PrometheusHandle};
+use serde_json::Value;
+use crate::state::AppState;
+use std::time::{Duration, Instant};
+
+const REQUEST_DURATION_METRIC: &str = "http_request_duration_seconds";
+const REQUEST_DURATION_BUCKETS: &[f64] = &[0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+const UPKEEP_INTERVAL_SECS: u64 = 5;
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+pub fn install() -> Result<PrometheusHandle, BuildError> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(Matcher::Full(REQUEST_DURATION_METRIC.to_string()), REQUEST_DURATION_BUCKETS)?
+        .install_recorder()?;
+
+    let upkeep = handle.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(UPKEEP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            upkeep.run_upkeep();
+        }
+    });
+
+    Ok(handle)
+}
+
+pub fn signature_produced(scheme: &'static str) {
+    counter!("signatures_produced_total", "scheme" => scheme).increment(1);
+}
+
+pub fn signature_verified(scheme: &'static str, valid: bool) {
+    let result = if valid { "valid" } else { "invalid" };
+    counter!("signatures_verified_total", "scheme" => scheme, "result" => result).increment(1);
+}
+
+pub fn keypair_generated(source: &'static str) {
+    counter!("keypairs_generated_total", "source" => source).increment(1);
+}
+
+pub fn feature_enabled(feature: &'static str, enabled: bool) {
+    gauge!("features_enabled", "feature" => feature).set(if enabled { 1.0 } else { 0.0 });
+}
+
+pub fn status_code_name(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::BAD_REQUEST => "bad_request",
+        StatusCode::UNAUTHORIZED => "unauthorized",
+        StatusCode::FORBIDDEN => "forbidden",
+        StatusCode::NOT_FOUND => "not_found",
+        StatusCode::METHOD_NOT_ALLOWED => "method_not_allowed",
+        StatusCode::REQUEST_TIMEOUT => "request_timeout",
+        StatusCode::PAYLOAD_TOO_LARGE => "payload_too_large",
+        StatusCode::UNSUPPORTED_MEDIA_TYPE => "unsupported_media_type",
+        StatusCode::UNPROCESSABLE_ENTITY => "unprocessable_entity",
+        StatusCode::TOO_MANY_REQUESTS => "too_many_requests",
+        StatusCode::INTERNAL_SERVER_ERROR => "internal_error",
+        StatusCode::BAD_GATEWAY => "bad_gateway",
+        StatusCode::SERVICE_UNAVAILABLE => "service_unavailable",
+        _ if status.is_client_error() => "client_error",
+        _ => "server_error",
+    }
+}
+
+pub async fn track_requests(request: Request, next: Next) -> Response<Body> {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let status = response.status();
+    let labels = [
+        ("method", method),
+        ("route", route.clone()),
+        ("status", status.as_u16().to_string()),
+    ];
+    counter!("http_requests_total", &labels).increment(1);
+    histogram!(REQUEST_DURATION_METRIC, &labels).record(elapsed);
+
+    if !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+
+    if !is_json {
+        counter!("handler_errors_total", "route" => route, "code" => status_code_name(status)).increment(1);
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+
+    let code = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(body) => match body.get("code").and_then(Value::as_str) {
+            Some(code) if !code.is_empty() => code.to_string(),
+            _ => status_code_name(status).to_string(),
+        },
+        Err(_) => status_code_name(status).to_string(),
+    };
+    counter!("handler_errors_total", "route" => route, "code" => code).increment(1);
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+pub async fn metrics(State(state): State<AppState>) -> ([(header::HeaderName, HeaderValue); 1], String) {
+    (
+        [(header::CONTENT_TYPE, HeaderValue::from_static("text/plain; version=0.0.4"))],
+        state.metrics.render(),
+    )
+}
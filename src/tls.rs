@@ -0,0 +1,146 @@
+use axum::serve::Listener;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    },
+    server::TlsStream,
+};
+use crate::config::TlsConfig;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+const RELOAD_INTERVAL_SECS: u64 = 10;
+const ALPN_PROTOCOLS: &[&[u8]] = &[b"h2", b"http/1.1"];
+
+fn load_server_config(cert_path: &Path, key_path: &Path) -> Result<Arc<ServerConfig>, String> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("failed to read certificate chain {}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("no certificates found in {}", cert_path.display()));
+    }
+
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| format!("failed to read private key {}: {}", key_path.display(), e))?;
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("invalid certificate or key: {}", e))?;
+    config.alpn_protocols = ALPN_PROTOCOLS.iter().map(|protocol| protocol.to_vec()).collect();
+
+    Ok(Arc::new(config))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+async fn watch_certificates(cert_path: PathBuf, key_path: PathBuf, current: Arc<RwLock<Arc<ServerConfig>>>) {
+    let mut last_modified = (modified(&cert_path), modified(&key_path));
+    let mut interval = tokio::time::interval(Duration::from_secs(RELOAD_INTERVAL_SECS));
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        let now_modified = (modified(&cert_path), modified(&key_path));
+        if now_modified == last_modified {
+            continue;
+        }
+        last_modified = now_modified;
+
+        match load_server_config(&cert_path, &key_path) {
+            Ok(config) => {
+                *current.write().unwrap() = config;
+                tracing::info!("Reloaded TLS certificate from {}", cert_path.display());
+            }
+            Err(e) => tracing::warn!("Keeping previous TLS certificate, reload failed: {}", e),
+        }
+    }
+}
+
+pub struct TlsListener {
+    tcp: TcpListener,
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+    handshakes: JoinSet<Option<(TlsStream<TcpStream>, SocketAddr)>>,
+}
+
+impl TlsListener {
+    pub fn new(tcp: TcpListener, tls: &TlsConfig) -> Result<TlsListener, String> {
+        let config = Arc::new(RwLock::new(load_server_config(&tls.cert_path, &tls.key_path)?));
+
+        tokio::spawn(watch_certificates(tls.cert_path.clone(), tls.key_path.clone(), config.clone()));
+
+        Ok(TlsListener {
+            tcp,
+            config,
+            handshakes: JoinSet::new(),
+        })
+    }
+
+    fn start_handshake(&mut self, stream: TcpStream, addr: SocketAddr) {
+        let acceptor = TlsAcceptor::from(self.config.read().unwrap().clone());
+
+        self.handshakes.spawn(async move {
+            match tokio::time::timeout(Duration::from_secs(HANDSHAKE_TIMEOUT_SECS), acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => Some((stream, addr)),
+                Ok(Err(e)) => {
+                    tracing::debug!("TLS handshake with {} failed: {}", addr, e);
+                    None
+                }
+                Err(_) => {
+                    tracing::debug!("TLS handshake with {} timed out", addr);
+                    None
+                }
+            }
+        });
+    }
+}
+
+fn is_connection_error(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::ConnectionRefused | io::ErrorKind::ConnectionAborted | io::ErrorKind::ConnectionReset
+    )
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            tokio::select! {
+                accepted = self.tcp.accept() => match accepted {
+                    Ok((stream, addr)) => self.start_handshake(stream, addr),
+                    Err(e) if is_connection_error(&e) => {}
+                    Err(e) => {
+                        tracing::error!("Failed to accept connection: {}", e);
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                },
+                Some(handshake) = self.handshakes.join_next(), if !self.handshakes.is_empty() => {
+                    if let Ok(Some(connection)) = handshake {
+                        return connection;
+                    }
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.tcp.local_addr()
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::transaction::VersionedTransaction;
+use axum::{Json, extract::State, http::StatusCode};
+use base64::{Engine as _, engine::general_purpose};
+use crate::message::message_signer;
+use crate::redact::Redacted;
+use crate::state::AppState;
+use crate::telemetry;
+
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    success: bool,
+    code: &'static str,
+    error: String,
+}
+
+#[derive(Deserialize)]
+pub struct SignTransactionRequest {
+    transaction: String,
+    #[serde(default)]
+    secret: Redacted<Value>,
+    #[serde(alias = "keyId")]
+    key_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SignTransactionResponse {
+    success: bool,
+    data: SignTransactionData,
+}
+
+#[derive(Serialize)]
+pub struct SignTransactionData {
+    transaction: String,
+    signature: String,
+    pubkey: String,
+    fully_signed: bool,
+}
+
+pub async fn sign_transaction(
+    State(state): State<AppState>,
+    Json(payload): Json<SignTransactionRequest>,
+) -> Result<Json<SignTransactionResponse>, (StatusCode, Json<ErrorResponse>)> {
+    if payload.transaction.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "missing_fields",
+            error: "Missing required fields".to_string(),
+        })));
+    }
+
+    let signer = match message_signer(&state, &payload.secret, payload.key_id.as_deref()) {
+        Ok(signer) => signer,
+        Err((status, code, error)) => {
+            return Err((status, Json(ErrorResponse {
+                success: false,
+                code,
+                error,
+            })));
+        }
+    };
+
+    let transaction_bytes = match general_purpose::STANDARD.decode(&payload.transaction) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_transaction",
+                error: "Invalid base64 encoding in transaction".to_string(),
+            })));
+        }
+    };
+
+    let mut transaction: VersionedTransaction = match bincode::deserialize(&transaction_bytes) {
+        Ok(transaction) => transaction,
+        Err(_) => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "invalid_transaction",
+                error: "Invalid transaction format".to_string(),
+            })));
+        }
+    };
+
+    let required_signatures = transaction.message.header().num_required_signatures as usize;
+    if transaction.signatures.len() != required_signatures {
+        return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+            success: false,
+            code: "invalid_transaction",
+            error: "Transaction signature count does not match its message header".to_string(),
+        })));
+    }
+
+    let pubkey = match signer.pubkey().await {
+        Ok(pubkey) => pubkey,
+        Err(e) => {
+            return Err((e.status(), Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
+
+    let index = match transaction.message.static_account_keys()[..required_signatures]
+        .iter()
+        .position(|key| *key == pubkey)
+    {
+        Some(index) => index,
+        None => {
+            return Err((StatusCode::BAD_REQUEST, Json(ErrorResponse {
+                success: false,
+                code: "not_a_signer",
+                error: "Key is not a required signer of this transaction".to_string(),
+            })));
+        }
+    };
+
+    let signature = match signer.sign(&transaction.message.serialize()).await {
+        Ok(signature) => {
+            telemetry::signature_produced("ed25519");
+            signature
+        }
+        Err(e) => {
+            return Err((e.status(), Json(ErrorResponse {
+                success: false,
+                code: e.code(),
+                error: e.to_string(),
+            })));
+        }
+    };
+    transaction.signatures[index] = signature;
+
+    let fully_signed = transaction.verify_with_results().iter().all(|valid| *valid);
+
+    let serialized = match bincode::serialize(&transaction) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse {
+                success: false,
+                code: "serialization_failed",
+                error: "Failed to serialize transaction".to_string(),
+            })));
+        }
+    };
+
+    let response = SignTransactionResponse {
+        success: true,
+        data: SignTransactionData {
+            transaction: general_purpose::STANDARD.encode(serialized),
+            signature: signature.to_string(),
+            pubkey: pubkey.to_string(),
+            fully_signed,
+        },
+    };
+
+    Ok(Json(response))
+}